@@ -6,6 +6,85 @@ export interface Project {
   path: string
   scene_count: number
   modified: number
+  description?: string
+  character_count?: number
+  location_count?: number
+  ingested?: boolean
+  analysis_coverage?: AnalysisCoverage
+  size_bytes?: number
+  tags?: string[]
+  favorite?: boolean
+  pinned?: number
+  archived?: boolean
+  last_opened?: number
+  last_section?: DashboardSection
+}
+
+export interface RecentProject {
+  id: string
+  path: string
+  opened_at: number
+  section?: DashboardSection
+}
+
+export interface AnalysisCoverage {
+  scenes: number
+  characters: number
+  locations: number
+}
+
+export interface ProjectManifest {
+  title?: string
+  author?: string
+  description?: string
+  logline?: string
+  genre?: string
+  draft_number?: number
+  contact?: ManifestContact
+  // Fields not modelled above are preserved as-is
+  [key: string]: unknown
+}
+
+export interface ManifestContact {
+  name?: string
+  email?: string
+  phone?: string
+  address?: string
+}
+
+export interface RootError {
+  root: string
+  error: string
+}
+
+export interface DiscoveryReport {
+  projects: Project[]
+  errors: RootError[]
+}
+
+export type ProjectChangeKind = 'added' | 'removed' | 'modified'
+
+export interface ProjectsChangedEvent {
+  change: ProjectChangeKind
+  project_id: string
+  path: string
+  project?: Project
+}
+
+export type SettingsChangeSource = 'app' | 'external'
+
+export interface SettingChange {
+  field: string
+  old: unknown
+  new: unknown
+}
+
+export interface SettingsChangedEvent {
+  source: SettingsChangeSource
+  changes: SettingChange[]
+  settings: SettingsConfig
+  /** A changed field only reaches a running daemon when it is restarted */
+  daemon_restart_required: boolean
 }
 
 export interface SystemInfo {
@@ -23,6 +102,29 @@ export interface CollisionInfo {
   suggested_names: string[]
 }
 
+export interface ProjectTemplate {
+  id: string
+  title: string
+  description?: string
+  builtin: boolean
+}
+
+export interface BundleManifest {
+  format_version: number
+  title: string
+  source_name: string
+  created_at: number
+  includes_analysis: boolean
+  includes_storage: boolean
+  files: BundleFile[]
+}
+
+export interface BundleFile {
+  path: string
+  size: number
+  sha256: string
+}
+
 export interface ImportProgress {
   phase: string
   line: string
@@ -36,10 +138,35 @@ export interface ImportResult {
 }
 
 // Settings types
+export interface ProjectRoot {
+  path: string
+  max_depth: number
+  include: string[]
+  exclude: string[]
+}
+
 export interface SettingsConfig {
   provider: string
   model?: string
   projects_root?: string
+  project_roots?: ProjectRoot[]
+  recent_projects_limit?: number
+  temperature?: number
+  max_tokens?: number
+  custom_providers?: CustomProvider[]
+  ollama_host?: string
+  khaos_tools_path?: string
+  khaos_wfl_path?: string
+}
+
+export interface CustomProvider {
+  id: string
+  title: string
+  base_url: string
+  api_key_env?: string
+  secret_ref?: string
+  default_model: string
+  openai_compatible: boolean
 }
 
 export interface ProviderInfo {
@@ -49,6 +176,10 @@ export interface ProviderInfo {
   default_model: string
   requires_key: boolean
   env_var?: string
+  base_url?: string
+  openai_compatible?: boolean
+  secret_ref?: string
+  custom?: boolean
 }
 
 export interface SettingsLoadResult {
@@ -60,6 +191,40 @@ export interface ProviderValidationResult {
   valid: boolean
   checks_run: string[]
   errors: string[]
+  connection?: ConnectionTest
+}
+
+export interface ProviderModel {
+  id: string
+  display_name?: string
+  context_window?: number
+  capabilities: string[]
+}
+
+export type ModelSource = 'api' | 'ollama' | 'catalog'
+
+export interface ProviderModelList {
+  provider: string
+  models: ProviderModel[]
+  source: ModelSource
+  fetched_at: number
+  error?: string
+}
+
+export type ConnectionOutcome =
+  | 'ok'
+  | 'auth_failed'
+  | 'model_not_found'
+  | 'rate_limited'
+  | 'unreachable'
+  | 'error'
+
+export interface ConnectionTest {
+  outcome: ConnectionOutcome
+  latency_ms?: number
+  status_code?: number
+  base_url?: string
+  message?: string
 }
 
 export interface DaemonCheckResult {
@@ -68,9 +233,44 @@ export interface DaemonCheckResult {
   error?: string
 }
 
+export interface SecretStoreStatus {
+  backend: 'secret-service' | 'encrypted-file'
+  locked: boolean
+  initialized: boolean
+}
+
+export interface ProviderKeyStatus {
+  provider: string
+  stored: boolean
+  env_set: boolean
+  error?: string
+}
+
+export interface OllamaModel {
+  name: string
+  size: number
+  family?: string
+  parameter_size?: string
+  quantization?: string
+  modified_at?: string
+}
+
 export interface OllamaStatus {
-  installed: boolean
+  host: string
+  reachable: boolean
+  version?: string
   model_available: boolean
+  models: OllamaModel[]
+  error?: string
+}
+
+export interface OllamaPullProgress {
+  model: string
+  status: string
+  digest?: string
+  total?: number
+  completed?: number
+  done: boolean
   error?: string
 }
 
@@ -192,6 +392,18 @@ export interface LocationAnalysis {
 export type AnalysisState = 'pending' | 'analyzing' | 'analyzed' | 'failed'
 export type DashboardSection = 'scenes' | 'characters' | 'locations'
 
+// Dashboard exports: `analysis` goes through export_analysis (json, markdown,
+// csv, html), `breakdown` through export_breakdown (csv, html) and `fountain`
+// through export_fountain
+export type ExportKind = 'analysis' | 'breakdown' | 'fountain'
+
+export interface ExportOption {
+  kind: ExportKind
+  format: string
+  label: string
+  extension: string
+}
+
 export interface AnalysisResult {
   success: boolean
   item_type: string
@@ -199,6 +411,15 @@ export interface AnalysisResult {
   error?: string
 }
 
+export interface AnalysisRun {
+  provider: string
+  model?: string
+  temperature?: number
+  max_tokens?: number
+  via: 'cli' | 'daemon'
+  recorded_at: number
+}
+
 export interface AnalysisIndex {
   scenes: string[]
   characters: string[]
@@ -220,6 +441,9 @@ export interface LocationDetail {
   analysis?: LocationAnalysis
 }
 
+export type ExportScope = 'scenes' | 'characters' | 'locations' | 'all'
+export type ExportFormat = 'json' | 'markdown' | 'csv' | 'html'
+
 export interface DaemonStatus {
   running: boolean
   project_path?: string
@@ -227,3 +451,35 @@ export interface DaemonStatus {
   busy: boolean
   queue_depth: number
 }
+
+export type ChangeKind = 'added' | 'removed' | 'moved' | 'modified'
+
+export interface FieldChange {
+  field: string
+  before?: string
+  after?: string
+}
+
+export interface SceneChange {
+  kind: ChangeKind
+  slugline: string
+  base_index?: number
+  other_index?: number
+  changes: FieldChange[]
+}
+
+export interface EntityChange {
+  kind: ChangeKind
+  name: string
+  changes: FieldChange[]
+}
+
+export interface ProjectComparison {
+  base_title: string
+  base_path: string
+  other_title: string
+  other_path: string
+  scenes: SceneChange[]
+  characters: EntityChange[]
+  locations: EntityChange[]
+}
@@ -162,3 +162,120 @@ pub async fn get_daemon_status(app: AppHandle, project_path: String) -> Result<D
 pub async fn start_daemon(project_path: String) -> Result<DaemonStatus, String> {
     services::dashboard::start_daemon(&project_path).await
 }
+
+/// Provider, model and generation parameters used for an entity's saved
+/// analysis, if recorded
+#[command]
+pub async fn get_analysis_run(
+    project_path: String,
+    item_id: String,
+) -> Result<Option<AnalysisRun>, String> {
+    let runs = services::analysis_runs::load_runs(&project_path)?;
+    Ok(services::analysis_runs::run_for_item(&runs, &item_id))
+}
+
+/// Export summaries and saved analyses to `output_path` (chosen by the user
+/// via the save dialog). `scope` is scenes, characters, locations or all;
+/// `format` is json, markdown, csv or html. Returns the written path.
+#[command]
+pub async fn export_analysis(
+    project_path: String,
+    scope: String,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let scope = services::export::ExportScope::parse(&scope)?;
+    let data = services::export::load_export_data(&project_path, scope).await?;
+    let content = services::export::render(&data, &format)?;
+
+    std::fs::write(&output_path, content).map_err(|e| format!("Failed to write export: {}", e))?;
+
+    tracing::info!(
+        "Exported {} analysis as {} to {}",
+        data.title,
+        format,
+        output_path
+    );
+    Ok(output_path)
+}
+
+/// Export production breakdown sheets (one per scene) to `output_path`.
+/// `format` is csv or html. Returns the written path.
+#[command]
+pub async fn export_breakdown(
+    project_path: String,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let data =
+        services::export::load_export_data(&project_path, services::export::ExportScope::Scenes)
+            .await?;
+    let sheets: Vec<_> = data
+        .scenes
+        .iter()
+        .flatten()
+        .map(services::breakdown::BreakdownSheet::from_scene)
+        .collect();
+    let content = services::breakdown::render(&data.title, &sheets, &format)?;
+
+    std::fs::write(&output_path, content)
+        .map_err(|e| format!("Failed to write breakdown: {}", e))?;
+
+    tracing::info!(
+        "Exported {} breakdown sheets for {} to {}",
+        sheets.len(),
+        data.title,
+        output_path
+    );
+    Ok(output_path)
+}
+
+/// Export the screenplay as Fountain with scene analysis as notes or boneyard
+/// (`style`, default notes). With `source_path` the original .fountain file is
+/// annotated; otherwise an outline of scene headings is written.
+#[command]
+pub async fn export_fountain(
+    project_path: String,
+    output_path: String,
+    style: Option<String>,
+    source_path: Option<String>,
+) -> Result<String, String> {
+    let style = services::fountain::AnnotationStyle::parse(style.as_deref().unwrap_or("notes"))?;
+    let content =
+        services::fountain::export_fountain(&project_path, style, source_path.as_deref()).await?;
+
+    std::fs::write(&output_path, content)
+        .map_err(|e| format!("Failed to write Fountain export: {}", e))?;
+
+    tracing::info!("Exported annotated Fountain to {}", output_path);
+    Ok(output_path)
+}
+
+/// Compare two projects (e.g. two drafts): added, removed and moved scenes,
+/// dialogue share and analysis changes
+#[command]
+pub async fn compare_projects(
+    base_path: String,
+    other_path: String,
+) -> Result<ProjectComparison, String> {
+    services::compare::compare_projects(&base_path, &other_path).await
+}
+
+/// Write a project comparison to `output_path` as json or markdown. Returns
+/// the written path.
+#[command]
+pub async fn export_comparison(
+    base_path: String,
+    other_path: String,
+    format: String,
+    output_path: String,
+) -> Result<String, String> {
+    let comparison = services::compare::compare_projects(&base_path, &other_path).await?;
+    let content = services::compare::render(&comparison, &format)?;
+
+    std::fs::write(&output_path, content)
+        .map_err(|e| format!("Failed to write comparison: {}", e))?;
+
+    tracing::info!("Exported comparison as {} to {}", format, output_path);
+    Ok(output_path)
+}
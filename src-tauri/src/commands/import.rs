@@ -2,7 +2,8 @@
 // Handles screenplay file parsing and project creation
 
 use crate::services;
-use crate::types::CollisionInfo;
+use crate::services::templates::NewProject;
+use crate::types::{CollisionInfo, Project, ProjectTemplate};
 use tauri::command;
 
 /// Validate an import file path
@@ -13,9 +14,13 @@ pub async fn validate_import_file(file_path: String) -> Result<(), String> {
 
 /// Check if importing a project with this title would cause a collision.
 /// Returns Some(CollisionInfo) if collision exists, None otherwise.
+/// `root` is one of the project roots (the first when omitted).
 #[command]
-pub async fn check_import_collision(title: String) -> Result<Option<CollisionInfo>, String> {
-    let root = services::discovery::get_projects_root()?;
+pub async fn check_import_collision(
+    title: String,
+    root: Option<String>,
+) -> Result<Option<CollisionInfo>, String> {
+    let root = services::discovery::target_root(root.as_deref())?;
 
     services::import::validate_title(&title)?;
 
@@ -33,13 +38,47 @@ pub async fn check_import_collision(title: String) -> Result<Option<CollisionInf
 }
 
 /// Resolve the target output path for a given title (without creating anything).
+/// `root` is one of the project roots (the first when omitted).
 #[command]
-pub async fn resolve_import_path(title: String) -> Result<String, String> {
-    let root = services::discovery::get_projects_root()?;
+pub async fn resolve_import_path(title: String, root: Option<String>) -> Result<String, String> {
+    let root = services::discovery::target_root(root.as_deref())?;
     services::import::validate_title(&title)?;
     services::import::resolve_target_path(&root, &title)
 }
 
+/// List templates available for new projects (built-in and user-supplied)
+#[command]
+pub async fn list_project_templates() -> Result<Vec<ProjectTemplate>, String> {
+    Ok(services::templates::list_templates())
+}
+
+/// Create an empty project from a template in one of the project roots (the
+/// first when `root` is omitted). `name` overrides the directory name derived
+/// from the title (e.g. a suggestion from check_import_collision for the same
+/// root).
+#[command]
+pub async fn create_project(
+    title: String,
+    author: Option<String>,
+    description: Option<String>,
+    template: Option<String>,
+    name: Option<String>,
+    root: Option<String>,
+) -> Result<Project, String> {
+    let root = services::discovery::target_root(root.as_deref())?;
+
+    services::templates::create_project(
+        &root,
+        &NewProject {
+            title: &title,
+            author: author.as_deref(),
+            description: description.as_deref(),
+            template: template.as_deref(),
+            name: name.as_deref(),
+        },
+    )
+}
+
 /// Start a parse operation for a screenplay file.
 /// Returns a request ID for tracking. Progress is streamed via Tauri events.
 #[command]
@@ -2,49 +2,73 @@
 // Handles project discovery, listing, and navigation
 
 use crate::services;
-use crate::types::Project;
+use crate::services::bundle::BundleOptions;
+use crate::services::project_state::{self, ProjectStates};
+use crate::types::{BundleManifest, DiscoveryReport, Project, ProjectManifest};
+use std::path::Path;
 use tauri::command;
 
-/// Discover projects from configured roots
+/// Scan all configured roots and apply the user's tags, favorites and pins.
+/// Fails only when every root failed; partial failures are logged by the scan.
+fn load_projects(include_archived: bool) -> Result<Vec<Project>, String> {
+    let roots = services::discovery::get_project_roots()?;
+    let report = services::discovery::discover_in_roots(&roots);
+
+    if !roots.is_empty() && report.errors.len() == roots.len() {
+        return Err(report.errors[0].error.clone());
+    }
+
+    project_state::apply_user_state(report.projects, include_archived)
+}
+
+/// Fail unless discovery knows every ID, so no organization state is saved for
+/// projects that don't exist
+fn require_known_projects(project_ids: &[String]) -> Result<(), String> {
+    let projects = load_projects(true)?;
+    match project_ids
+        .iter()
+        .find(|id| !projects.iter().any(|p| &p.id == *id))
+    {
+        Some(id) => Err(format!("Project not found: {}", id)),
+        None => Ok(()),
+    }
+}
+
+/// Discover projects from configured roots (archived projects only on request)
 #[command]
-pub async fn discover_projects() -> Result<Vec<Project>, String> {
-    let root = services::discovery::get_projects_root()?;
-    tracing::info!("Discovering projects from: {}", root);
-    services::discovery::discover_projects(&root)
+pub async fn discover_projects(include_archived: Option<bool>) -> Result<Vec<Project>, String> {
+    load_projects(include_archived.unwrap_or(false))
 }
 
-/// Search projects by title or author
+/// Discover projects and report which roots could not be scanned
+#[command]
+pub async fn get_discovery_report() -> Result<DiscoveryReport, String> {
+    let roots = services::discovery::get_project_roots()?;
+    tracing::info!("Discovering projects from {} roots", roots.len());
+    let mut report = services::discovery::discover_in_roots(&roots);
+    report.projects = project_state::apply_user_state(report.projects, false)?;
+    Ok(report)
+}
+
+/// Search projects with fuzzy matching and filters (author:, modified:>YYYY-MM-DD,
+/// scenes:>N, analyzed:none|partial|full), best matches first
 #[command]
 pub async fn search_projects(query: String) -> Result<Vec<Project>, String> {
-    if query.trim().is_empty() {
+    let query = services::search::SearchQuery::parse(&query)?;
+    let projects = load_projects(query.includes_archived())?;
+
+    if query.is_empty() {
         // Empty query returns all projects
-        return discover_projects().await;
+        return Ok(projects);
     }
 
-    let root = services::discovery::get_projects_root()?;
-    let mut projects = services::discovery::discover_projects(&root)?;
-
-    let query_lower = query.to_lowercase();
-
-    // Filter by title, author, or path matching
-    projects.retain(|proj| {
-        proj.title.to_lowercase().contains(&query_lower)
-            || proj
-                .author
-                .as_ref()
-                .map(|a| a.to_lowercase().contains(&query_lower))
-                .unwrap_or(false)
-            || proj.path.to_lowercase().contains(&query_lower)
-    });
-
-    Ok(projects)
+    Ok(services::search::search_projects(projects, &query))
 }
 
 /// Get single project details by ID
 #[command]
 pub async fn get_project(project_id: String) -> Result<Project, String> {
-    let root = services::discovery::get_projects_root()?;
-    let projects = services::discovery::discover_projects(&root)?;
+    let projects = load_projects(true)?;
 
     projects
         .into_iter()
@@ -53,34 +77,93 @@ pub async fn get_project(project_id: String) -> Result<Project, String> {
 }
 
 /// Set a project as active (adds to recent projects list)
-/// Loads existing recent projects, inserts this project at the front,
-/// limits to 5 most recent, and saves back to config
+/// Moves this project to the front of the history with the current time,
+/// trims the history to the configured length, and saves back to config
 #[command]
 pub async fn set_active_project(project_id: String) -> Result<(), String> {
-    // Load current recent projects list
-    let mut recent_ids = services::discovery::load_recent_projects()?;
+    let project = load_projects(true)?
+        .into_iter()
+        .find(|p| p.id == project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
 
-    // Remove project_id if it's already in the list
-    recent_ids.retain(|id| id != &project_id);
+    let limit = services::config::load_settings()
+        .unwrap_or_default()
+        .recent_projects_limit;
+    let opened_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
 
-    // Insert at the front
-    recent_ids.insert(0, project_id.clone());
+    services::config::blocking(move || {
+        services::discovery::update_recent_projects(limit, |history| {
+            services::discovery::record_recent_project(history, &project, opened_at, limit)
+        })
+    })
+    .await?;
 
-    // Limit to 5 most recent
-    recent_ids.truncate(5);
+    tracing::info!("Set project as active: {}", project_id);
+    Ok(())
+}
 
-    // Save back to config
-    services::discovery::save_recent_projects(&recent_ids)?;
+/// Get recently opened projects, most recent first.
+/// History entries for projects that no longer exist are pruned.
+#[command]
+pub async fn get_recent_projects() -> Result<Vec<Project>, String> {
+    let projects = load_projects(true)?;
+    let limit = services::config::load_settings()
+        .unwrap_or_default()
+        .recent_projects_limit;
 
-    tracing::info!("Set project as active: {}", project_id);
+    let mut history = services::discovery::load_recent_projects()?;
+    let before = history.len();
+    let recent = services::discovery::resolve_recent_projects(&mut history, &projects);
+
+    if history.len() != before {
+        tracing::info!("Pruned {} missing recent projects", before - history.len());
+        // Prune again under the lock so entries added meanwhile are kept
+        services::config::blocking(move || {
+            services::discovery::update_recent_projects(limit, |history| {
+                services::discovery::resolve_recent_projects(history, &projects);
+            })
+        })
+        .await?;
+    }
+
+    Ok(recent)
+}
+
+/// Remember the dashboard section shown for a recently opened project, so the
+/// dashboard can return to it next time
+#[command]
+pub async fn set_recent_project_section(
+    project_path: String,
+    section: String,
+) -> Result<(), String> {
+    if !matches!(section.as_str(), "scenes" | "characters" | "locations") {
+        return Err(format!("Unknown section: {}", section));
+    }
+
+    let limit = services::config::load_settings()
+        .unwrap_or_default()
+        .recent_projects_limit;
+    let path = project_path.clone();
+    let recorded = services::config::blocking(move || {
+        services::discovery::update_recent_projects(limit, |history| {
+            services::discovery::record_recent_section(history, &path, &section)
+        })
+    })
+    .await?;
+
+    if !recorded {
+        tracing::debug!("Project not in recent history: {}", project_path);
+    }
     Ok(())
 }
 
 /// Delete a project by removing it from the filesystem
 #[command]
 pub async fn delete_project(project_id: String) -> Result<(), String> {
-    let root = services::discovery::get_projects_root()?;
-    let projects = services::discovery::discover_projects(&root)?;
+    let projects = load_projects(true)?;
 
     // Find project by ID
     let project = projects
@@ -94,10 +177,131 @@ pub async fn delete_project(project_id: String) -> Result<(), String> {
     })?;
 
     // Remove from recent projects if it's there
-    let mut recent_ids = services::discovery::load_recent_projects().unwrap_or_default();
-    recent_ids.retain(|id| id != &project_id);
-    let _ = services::discovery::save_recent_projects(&recent_ids);
+    let limit = services::config::load_settings()
+        .unwrap_or_default()
+        .recent_projects_limit;
+    let id = project_id.clone();
+    let _ = services::config::blocking(move || {
+        let _ = services::discovery::update_recent_projects(limit, |history| {
+            history.retain(|entry| entry.id != id)
+        });
+        project_state::update(|states| states.remove(&id))
+    })
+    .await;
 
     tracing::info!("Deleted project: {} at {}", project_id, project.path);
     Ok(())
 }
+
+/// Replace a project's tags
+#[command]
+pub async fn set_project_tags(project_id: String, tags: Vec<String>) -> Result<(), String> {
+    require_known_projects(std::slice::from_ref(&project_id))?;
+    services::config::blocking(move || {
+        project_state::update(|states| states.set_tags(&project_id, tags.clone()))
+    })
+    .await
+}
+
+/// Mark or unmark a project as a favorite
+#[command]
+pub async fn set_project_favorite(project_id: String, favorite: bool) -> Result<(), String> {
+    require_known_projects(std::slice::from_ref(&project_id))?;
+    services::config::blocking(move || {
+        project_state::update(|states| states.set_favorite(&project_id, favorite))
+    })
+    .await
+}
+
+/// Archive a project (hidden from default discovery) or restore it
+#[command]
+pub async fn set_project_archived(project_id: String, archived: bool) -> Result<(), String> {
+    require_known_projects(std::slice::from_ref(&project_id))?;
+    let id = project_id.clone();
+    services::config::blocking(move || {
+        project_state::update(|states| states.set_archived(&id, archived))
+    })
+    .await?;
+    tracing::info!(
+        "{} project: {}",
+        if archived { "Archived" } else { "Unarchived" },
+        project_id
+    );
+    Ok(())
+}
+
+/// Pin a project to the end of the pinned list, or unpin it
+#[command]
+pub async fn set_project_pinned(project_id: String, pinned: bool) -> Result<(), String> {
+    require_known_projects(std::slice::from_ref(&project_id))?;
+    services::config::blocking(move || {
+        project_state::update(|states| states.set_pinned(&project_id, pinned))
+    })
+    .await
+}
+
+/// Replace the pinned order; projects left out are unpinned
+#[command]
+pub async fn reorder_pinned_projects(project_ids: Vec<String>) -> Result<(), String> {
+    require_known_projects(&project_ids)?;
+    services::config::blocking(move || {
+        project_state::update(|states| states.reorder_pinned(project_ids.clone()))
+    })
+    .await
+}
+
+/// List all tags in use across projects
+#[command]
+pub async fn list_project_tags() -> Result<Vec<String>, String> {
+    Ok(ProjectStates::load()?.all_tags())
+}
+
+/// Read a project's manifest.json
+#[command]
+pub async fn get_manifest(project_path: String) -> Result<ProjectManifest, String> {
+    services::manifest::read_manifest(Path::new(&project_path))
+}
+
+/// Update a project's manifest.json, preserving fields the app does not model
+#[command]
+pub async fn update_manifest(
+    project_path: String,
+    manifest: ProjectManifest,
+) -> Result<ProjectManifest, String> {
+    services::manifest::update_manifest(Path::new(&project_path), &manifest)
+}
+
+/// Export a project to a portable bundle archive, optionally leaving out
+/// saved analysis and the story storage database
+#[command]
+pub async fn export_project_bundle(
+    project_path: String,
+    output_path: String,
+    exclude_analysis: Option<bool>,
+    exclude_storage: Option<bool>,
+) -> Result<BundleManifest, String> {
+    let options = BundleOptions {
+        exclude_analysis: exclude_analysis.unwrap_or(false),
+        exclude_storage: exclude_storage.unwrap_or(false),
+    };
+    services::bundle::export_bundle(Path::new(&project_path), Path::new(&output_path), options)
+}
+
+/// Read a bundle's contents listing (title, files) before importing it
+#[command]
+pub async fn inspect_project_bundle(bundle_path: String) -> Result<BundleManifest, String> {
+    services::bundle::inspect_bundle(Path::new(&bundle_path))
+}
+
+/// Verify and unpack a bundle into one of the project roots (the first when
+/// `root` is omitted). `name` overrides the directory name derived from the
+/// bundle title.
+#[command]
+pub async fn import_project_bundle(
+    bundle_path: String,
+    name: Option<String>,
+    root: Option<String>,
+) -> Result<Project, String> {
+    let root = services::discovery::target_root(root.as_deref())?;
+    services::bundle::import_bundle(Path::new(&bundle_path), &root, name.as_deref())
+}
@@ -1,10 +1,12 @@
 // Settings Commands
 // Handles configuration loading/saving, provider validation, and daemon connectivity
 
-use crate::events::{app_events, SettingsChangedEvent};
+use crate::events::{app_events, OllamaPullProgressEvent, SettingsChangeSource};
 use crate::services;
 use crate::types::{
-    DaemonCheckResult, ProviderInfo, ProviderValidationResult, SettingsConfig, SettingsLoadResult,
+    ConnectionTest, DaemonCheckResult, OllamaModel, OllamaStatus, ProviderInfo, ProviderKeyStatus,
+    ProviderModelList, ProviderValidationResult, SecretStoreStatus, SettingsConfig,
+    SettingsLoadResult, MAX_RECENT_PROJECTS_LIMIT,
 };
 use tauri::{command, AppHandle, Emitter};
 
@@ -12,24 +14,31 @@ use tauri::{command, AppHandle, Emitter};
 #[command]
 pub async fn load_settings() -> Result<SettingsLoadResult, String> {
     let config = services::config::load_settings()?;
-    let providers = ProviderInfo::all();
+    let providers = config.providers();
     Ok(SettingsLoadResult { config, providers })
 }
 
 /// Save settings and notify all windows
 #[command]
 pub async fn save_settings(app: AppHandle, config: SettingsConfig) -> Result<(), String> {
-    services::config::save_settings(&config)?;
+    let errors = services::settings::validate_custom_providers(&config.custom_providers);
+    if !errors.is_empty() {
+        return Err(format!("Invalid custom providers: {}", errors.join("; ")));
+    }
+    if !(1..=MAX_RECENT_PROJECTS_LIMIT).contains(&config.recent_projects_limit) {
+        return Err(format!(
+            "Recent projects limit must be between 1 and {} (got {})",
+            MAX_RECENT_PROJECTS_LIMIT, config.recent_projects_limit
+        ));
+    }
 
-    let _ = app.emit(
-        app_events::APP_SETTINGS_CHANGED,
-        SettingsChangedEvent {
-            provider: config.provider.clone(),
-            model: config.model.clone(),
-        },
-    );
+    let previous = services::config::load_settings().ok();
+    let saved = config.clone();
+    services::config::blocking(move || services::config::save_settings(&saved)).await?;
 
-    tracing::info!("Settings saved and event emitted");
+    services::watcher::publish_settings(&app, previous, config, SettingsChangeSource::App).await;
+
+    tracing::info!("Settings saved");
     Ok(())
 }
 
@@ -51,3 +60,194 @@ pub async fn validate_provider_config(
 ) -> Result<ProviderValidationResult, String> {
     Ok(services::settings::validate_provider(&config).await)
 }
+
+/// Make a live request to the provider to check the key and model. Opt-in
+/// since it contacts the provider; `base_url` points it at a stand-in server.
+#[command]
+pub async fn test_provider_connection(
+    config: SettingsConfig,
+    base_url: Option<String>,
+) -> Result<ProviderValidationResult, String> {
+    Ok(services::settings::test_connection(&config, base_url.as_deref()).await)
+}
+
+/// Models a provider offers, from its API when reachable or the built-in
+/// catalog otherwise. Results are cached; `refresh` asks the provider again.
+#[command]
+pub async fn list_provider_models(
+    provider_id: String,
+    refresh: Option<bool>,
+) -> Result<ProviderModelList, String> {
+    let settings = services::config::load_settings()?;
+    let provider = settings
+        .find_provider(&provider_id)
+        .ok_or_else(|| format!("Unknown provider: {}", provider_id))?;
+    let base_url = services::settings::provider_base_url(&settings, &provider, None);
+    let api_key = services::settings::provider_api_key(&provider);
+
+    Ok(services::provider_models::list_models(
+        &provider,
+        base_url.as_deref(),
+        api_key.as_deref(),
+        refresh.unwrap_or(false),
+    )
+    .await)
+}
+
+/// Report which secret store backend is in use and whether it is unlocked
+#[command]
+pub async fn get_secret_store_status() -> Result<SecretStoreStatus, String> {
+    services::keychain::status()
+}
+
+/// Unlock the encrypted secret file for this session (no-op with Secret Service)
+#[command]
+pub async fn unlock_secret_store(passphrase: String) -> Result<SecretStoreStatus, String> {
+    services::keychain::unlock(&passphrase)?;
+    services::keychain::status()
+}
+
+/// Forget the session passphrase
+#[command]
+pub async fn lock_secret_store() -> Result<SecretStoreStatus, String> {
+    services::keychain::lock();
+    services::keychain::status()
+}
+
+/// Save an API key for a provider in the secret store
+#[command]
+pub async fn set_provider_key(provider_id: String, api_key: String) -> Result<(), String> {
+    let provider = find_provider(&provider_id)?;
+    if !provider.requires_key {
+        return Err(format!("{} does not use an API key", provider.title));
+    }
+
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err("API key cannot be empty".to_string());
+    }
+
+    services::keychain::store_secret(&services::settings::secret_key(&provider), api_key)?;
+    tracing::info!("Stored API key for {}", provider_id);
+    Ok(())
+}
+
+/// Check whether a key is stored for a provider (and whether its env var is set)
+#[command]
+pub async fn get_provider_key_status(provider_id: String) -> Result<ProviderKeyStatus, String> {
+    let provider = find_provider(&provider_id)?;
+    Ok(services::settings::provider_key_status(&provider))
+}
+
+/// Check a provider's key with a live request to the provider, using the key
+/// analysis would use (its env var, else the stored key)
+#[command]
+pub async fn test_provider_key(provider_id: String) -> Result<ConnectionTest, String> {
+    let mut settings = services::config::load_settings()?;
+    let provider = settings
+        .find_provider(&provider_id)
+        .ok_or_else(|| format!("Unknown provider: {}", provider_id))?;
+    if !provider.requires_key {
+        return Err(format!("{} does not use an API key", provider.title));
+    }
+    // The saved model belongs to the selected provider
+    if settings.provider != provider_id {
+        settings.provider = provider_id;
+        settings.model = None;
+    }
+
+    services::settings::test_connection(&settings, None)
+        .await
+        .connection
+        .ok_or_else(|| "Connection test did not run".to_string())
+}
+
+/// Remove a provider's stored API key
+#[command]
+pub async fn clear_provider_key(provider_id: String) -> Result<(), String> {
+    let provider = find_provider(&provider_id)?;
+    services::keychain::delete_secret(&services::settings::secret_key(&provider))?;
+    tracing::info!("Cleared API key for {}", provider_id);
+    Ok(())
+}
+
+/// Check the Ollama server and whether a model is installed. Host and model
+/// default to the saved settings.
+#[command]
+pub async fn get_ollama_status(
+    host: Option<String>,
+    model: Option<String>,
+) -> Result<OllamaStatus, String> {
+    let settings = services::config::load_settings().unwrap_or_default();
+    let host = ollama_host(host, &settings);
+    let model = model
+        .filter(|m| !m.trim().is_empty())
+        .or_else(|| {
+            if settings.provider == "ollama" {
+                settings.effective_model()
+            } else {
+                ProviderInfo::find("ollama").map(|p| p.default_model)
+            }
+        })
+        .unwrap_or_default();
+    Ok(services::ollama::get_status(&host, &model).await)
+}
+
+/// List models installed on the Ollama server
+#[command]
+pub async fn list_ollama_models(host: Option<String>) -> Result<Vec<OllamaModel>, String> {
+    let settings = services::config::load_settings().unwrap_or_default();
+    services::ollama::list_models(&ollama_host(host, &settings)).await
+}
+
+/// Pull a model onto the Ollama server, emitting progress events until it
+/// completes or fails
+#[command]
+pub async fn pull_ollama_model(
+    app: AppHandle,
+    model: String,
+    host: Option<String>,
+) -> Result<(), String> {
+    let model = model.trim().to_string();
+    if model.is_empty() {
+        return Err("Model name cannot be empty".to_string());
+    }
+
+    let settings = services::config::load_settings().unwrap_or_default();
+    let host = ollama_host(host, &settings);
+    tracing::info!("Pulling Ollama model {} from {}", model, host);
+
+    let result = services::ollama::pull_model(&host, &model, |progress| {
+        let _ = app.emit(app_events::OLLAMA_PULL_PROGRESS, progress);
+    })
+    .await;
+
+    if let Err(ref e) = result {
+        let _ = app.emit(
+            app_events::OLLAMA_PULL_PROGRESS,
+            OllamaPullProgressEvent {
+                model: model.clone(),
+                status: "error".to_string(),
+                digest: None,
+                total: None,
+                completed: None,
+                done: true,
+                error: Some(e.clone()),
+            },
+        );
+    }
+    result
+}
+
+/// Explicit host, else the one in settings, else OLLAMA_HOST or the default
+fn ollama_host(host: Option<String>, settings: &SettingsConfig) -> String {
+    let configured = host.or_else(|| settings.ollama_host.clone());
+    services::ollama::resolve_host(configured.as_deref())
+}
+
+/// Look up a built-in or custom provider from the saved settings
+fn find_provider(provider_id: &str) -> Result<ProviderInfo, String> {
+    services::config::load_settings()?
+        .find_provider(provider_id)
+        .ok_or_else(|| format!("Unknown provider: {}", provider_id))
+}
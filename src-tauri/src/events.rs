@@ -1,6 +1,7 @@
 // Event Bridge
 // Maps daemon topics to Tauri app events for frontend subscription
 
+use crate::types::{Project, SettingsConfig};
 use serde::Serialize;
 
 /// Normalized Tauri event names (frontend-facing)
@@ -13,6 +14,8 @@ pub mod app_events {
     pub const DAEMON_STATUS: &str = "daemon:status";
     pub const APP_SETTINGS_CHANGED: &str = "app:settings-changed";
     pub const APP_PROJECT_SELECTED: &str = "app:project-selected";
+    pub const PROJECTS_CHANGED: &str = "projects:changed";
+    pub const OLLAMA_PULL_PROGRESS: &str = "ollama:pull-progress";
 }
 
 /// Payload emitted when analysis progresses
@@ -61,11 +64,48 @@ pub struct ParserCompletedEvent {
     pub error: Option<String>,
 }
 
+/// Where a settings change came from
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsChangeSource {
+    /// Saved from the Settings window
+    App,
+    /// The config file was edited outside the app
+    External,
+}
+
+/// One settings field that changed, with its JSON values before and after
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SettingChange {
+    pub field: String,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
 /// Payload emitted when settings change
 #[derive(Debug, Clone, Serialize)]
 pub struct SettingsChangedEvent {
-    pub provider: String,
-    pub model: Option<String>,
+    pub source: SettingsChangeSource,
+    /// Changed fields, ordered by name
+    pub changes: Vec<SettingChange>,
+    /// The settings now in effect
+    pub settings: SettingsConfig,
+    /// A changed field only reaches a running daemon when it is restarted
+    pub daemon_restart_required: bool,
+}
+
+/// Payload emitted for each status line of an Ollama model pull
+#[derive(Debug, Clone, Serialize)]
+pub struct OllamaPullProgressEvent {
+    pub model: String,
+    pub status: String,
+    pub digest: Option<String>,
+    /// Bytes of the layer being downloaded
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    /// Set on the final event, whether the pull succeeded or failed
+    pub done: bool,
+    pub error: Option<String>,
 }
 
 /// Payload emitted when a project is selected
@@ -74,3 +114,22 @@ pub struct ProjectSelectedEvent {
     pub project_title: String,
     pub project_path: String,
 }
+
+/// Kind of change observed for a project on disk
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Payload emitted when a project under a watched root changes
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectsChangedEvent {
+    pub change: ProjectChangeKind,
+    pub project_id: String,
+    pub path: String,
+    /// Updated project record (absent when removed)
+    pub project: Option<Project>,
+}
@@ -17,18 +17,52 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                services::watcher::ensure_project_watcher(&handle).await;
+                services::watcher::ensure_config_watcher(&handle).await;
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Projects commands
             commands::projects::discover_projects,
+            commands::projects::get_discovery_report,
             commands::projects::search_projects,
             commands::projects::get_project,
             commands::projects::set_active_project,
+            commands::projects::get_recent_projects,
+            commands::projects::set_recent_project_section,
             commands::projects::delete_project,
+            commands::projects::set_project_tags,
+            commands::projects::set_project_favorite,
+            commands::projects::set_project_archived,
+            commands::projects::set_project_pinned,
+            commands::projects::reorder_pinned_projects,
+            commands::projects::list_project_tags,
+            commands::projects::get_manifest,
+            commands::projects::update_manifest,
+            commands::projects::export_project_bundle,
+            commands::projects::inspect_project_bundle,
+            commands::projects::import_project_bundle,
             // Settings commands
             commands::settings::load_settings,
             commands::settings::save_settings,
             commands::settings::check_daemon_connection,
             commands::settings::validate_provider_config,
+            commands::settings::test_provider_connection,
+            commands::settings::list_provider_models,
+            commands::settings::get_secret_store_status,
+            commands::settings::unlock_secret_store,
+            commands::settings::lock_secret_store,
+            commands::settings::set_provider_key,
+            commands::settings::get_provider_key_status,
+            commands::settings::test_provider_key,
+            commands::settings::clear_provider_key,
+            commands::settings::get_ollama_status,
+            commands::settings::list_ollama_models,
+            commands::settings::pull_ollama_model,
             // Dashboard commands
             commands::dashboard::get_scenes,
             commands::dashboard::get_characters,
@@ -45,10 +79,18 @@ fn main() {
             commands::dashboard::get_analysis_results,
             commands::dashboard::get_daemon_status,
             commands::dashboard::start_daemon,
+            commands::dashboard::get_analysis_run,
+            commands::dashboard::export_analysis,
+            commands::dashboard::export_breakdown,
+            commands::dashboard::export_fountain,
+            commands::dashboard::compare_projects,
+            commands::dashboard::export_comparison,
             // Import commands
             commands::import::validate_import_file,
             commands::import::check_import_collision,
             commands::import::resolve_import_path,
+            commands::import::list_project_templates,
+            commands::import::create_project,
             commands::import::start_parse,
             commands::import::get_parse_progress,
             commands::import::cancel_parse,
@@ -0,0 +1,157 @@
+// Analysis Runs Service
+// Records the provider, model and generation parameters used for saved
+// analyses in metadata/analysis_runs.json, next to the analysis files
+
+use crate::services::config;
+use crate::types::{AnalysisRun, SettingsConfig};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RUNS_FILE: &str = "analysis_runs.json";
+
+/// Key covering every entity analyzed by a whole-screenplay run
+pub const SCREENPLAY_KEY: &str = "screenplay";
+
+fn runs_path(kspd_path: &str) -> PathBuf {
+    Path::new(kspd_path).join("metadata").join(RUNS_FILE)
+}
+
+/// Build a run record from the current settings
+pub fn run_from_settings(settings: &SettingsConfig, via: &str, recorded_at: i64) -> AnalysisRun {
+    AnalysisRun {
+        provider: settings.provider.clone(),
+        model: settings.effective_model(),
+        temperature: settings.temperature,
+        max_tokens: settings.max_tokens,
+        via: via.to_string(),
+        recorded_at,
+    }
+}
+
+/// Runs keyed by entity ID, section ("scenes", "characters", "locations") or
+/// SCREENPLAY_KEY
+pub fn load_runs(kspd_path: &str) -> Result<BTreeMap<String, AnalysisRun>, String> {
+    match fs::read_to_string(runs_path(kspd_path)) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", RUNS_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", RUNS_FILE, e)),
+    }
+}
+
+/// Add or replace the run for `key`. Concurrent analyses of the same project
+/// are serialized by a lock on the runs file so none of their entries is lost.
+pub fn record_run(kspd_path: &str, key: &str, run: AnalysisRun) -> Result<(), String> {
+    let path = runs_path(kspd_path);
+    let _lock = config::FileLock::acquire(&path)?;
+
+    let mut runs = load_runs(kspd_path)?;
+    runs.insert(key.to_string(), run);
+
+    let json = serde_json::to_string_pretty(&runs)
+        .map_err(|e| format!("Failed to serialize analysis runs: {}", e))?;
+    config::write_atomic(&path, json.as_bytes())
+}
+
+/// The most recent run that covered an entity: its own entry, or a later
+/// section-wide or whole-screenplay run
+pub fn run_for_item(runs: &BTreeMap<String, AnalysisRun>, item_id: &str) -> Option<AnalysisRun> {
+    let section = if item_id.starts_with("scn_") {
+        Some("scenes")
+    } else if item_id.starts_with("chr_") {
+        Some("characters")
+    } else if item_id.starts_with("loc_") {
+        Some("locations")
+    } else {
+        None
+    };
+
+    [Some(item_id), section, Some(SCREENPLAY_KEY)]
+        .into_iter()
+        .flatten()
+        .filter_map(|key| runs.get(key))
+        .max_by_key(|run| run.recorded_at)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(model: &str, recorded_at: i64) -> AnalysisRun {
+        AnalysisRun {
+            provider: "openai".to_string(),
+            model: Some(model.to_string()),
+            temperature: Some(0.2),
+            max_tokens: None,
+            via: "cli".to_string(),
+            recorded_at,
+        }
+    }
+
+    #[test]
+    fn test_record_and_load_runs() {
+        let dir = std::env::temp_dir().join("khaos_analysis_runs.kspd");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("metadata")).unwrap();
+        let path = dir.to_str().unwrap();
+
+        assert!(load_runs(path).unwrap().is_empty());
+        record_run(path, "scn_001", run("gpt-4o", 10)).unwrap();
+        record_run(path, "scn_002", run("gpt-4o-mini", 20)).unwrap();
+
+        let runs = load_runs(path).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs["scn_001"].model.as_deref(), Some("gpt-4o"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_concurrent_records_are_kept() {
+        let dir = std::env::temp_dir().join("khaos_analysis_runs_concurrent.kspd");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("metadata")).unwrap();
+        let path = dir.to_str().unwrap().to_string();
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    record_run(&path, &format!("scn_{:03}", i), run("gpt-4o", i)).unwrap()
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(load_runs(&path).unwrap().len(), 8);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_run_for_item_prefers_newest_covering_run() {
+        let mut runs = BTreeMap::new();
+        runs.insert("scn_001".to_string(), run("own", 10));
+        runs.insert("scenes".to_string(), run("section", 20));
+        runs.insert(SCREENPLAY_KEY.to_string(), run("screenplay", 5));
+
+        assert_eq!(
+            run_for_item(&runs, "scn_001").unwrap().model.as_deref(),
+            Some("section")
+        );
+        assert_eq!(
+            run_for_item(&runs, "chr_001").unwrap().model.as_deref(),
+            Some("screenplay")
+        );
+
+        runs.insert("scn_001".to_string(), run("rerun", 30));
+        assert_eq!(
+            run_for_item(&runs, "scn_001").unwrap().model.as_deref(),
+            Some("rerun")
+        );
+    }
+}
@@ -0,0 +1,308 @@
+// Breakdown Service
+// Production breakdown sheets (cast, extras, location, day/night, pages,
+// props) built from saved scene analysis, exported as CSV or printable HTML
+
+use crate::services::export::csv_line;
+use crate::services::report::{self, escape};
+use crate::types::SceneDetail;
+
+/// One scene's breakdown. Analysis fields take precedence; the scene summary
+/// and slugline fill in what the analysis does not provide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakdownSheet {
+    pub scene_id: String,
+    pub scene_number: String,
+    pub heading: String,
+    /// INT, EXT or INT/EXT
+    pub int_ext: String,
+    pub location: String,
+    pub day_night: String,
+    pub pages: String,
+    pub scene_type: String,
+    pub cast: Vec<String>,
+    pub extras: Vec<String>,
+    pub props: Vec<String>,
+    pub analyzed: bool,
+}
+
+impl BreakdownSheet {
+    pub fn from_scene(scene: &SceneDetail) -> Self {
+        let s = &scene.summary;
+        let a = scene.analysis.as_ref();
+        let non_empty = |v: Option<&String>| v.filter(|v| !v.trim().is_empty()).cloned();
+
+        let heading =
+            non_empty(a.and_then(|a| a.heading.as_ref())).unwrap_or_else(|| s.slugline.clone());
+        let (int_ext, location, slug_time) = parse_slugline(&heading);
+
+        let cast = match a {
+            Some(a) if !a.speaking_chars.is_empty() => a.speaking_chars.clone(),
+            _ => s.characters.clone(),
+        };
+
+        let mut extras = a.map(|a| a.non_speaking_chars.clone()).unwrap_or_default();
+        for detail in a.iter().flat_map(|a| a.non_speaking_details.iter()) {
+            if !extras.contains(detail) {
+                extras.push(detail.clone());
+            }
+        }
+
+        BreakdownSheet {
+            scene_id: s.id.clone(),
+            scene_number: non_empty(a.and_then(|a| a.scene_number.as_ref()))
+                .unwrap_or_else(|| s.index.to_string()),
+            heading,
+            int_ext,
+            location,
+            day_night: non_empty(a.and_then(|a| a.time_of_day.as_ref())).unwrap_or(slug_time),
+            pages: non_empty(a.and_then(|a| a.length_pages.as_ref())).unwrap_or_default(),
+            scene_type: non_empty(a.and_then(|a| a.scene_type.as_ref())).unwrap_or_default(),
+            cast,
+            extras,
+            props: a.map(|a| a.visual_symbols.clone()).unwrap_or_default(),
+            analyzed: a.is_some(),
+        }
+    }
+}
+
+/// Split a slugline such as "INT. KITCHEN - NIGHT" into its interior/exterior
+/// marker, location and time of day (empty when absent)
+fn parse_slugline(slugline: &str) -> (String, String, String) {
+    let slugline = slugline.trim();
+
+    let mut int_ext = String::new();
+    let mut rest = slugline;
+    for (prefix, marker) in [
+        ("INT./EXT.", "INT/EXT"),
+        ("INT/EXT.", "INT/EXT"),
+        ("INT/EXT", "INT/EXT"),
+        ("I/E.", "INT/EXT"),
+        ("INT.", "INT"),
+        ("EXT.", "EXT"),
+        ("EST.", "EXT"),
+    ] {
+        let matches = slugline
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix));
+        if matches {
+            int_ext = marker.to_string();
+            rest = slugline[prefix.len()..].trim_start();
+            break;
+        }
+    }
+
+    match rest.rsplit_once(" - ") {
+        Some((location, time)) => (
+            int_ext,
+            location.trim().to_string(),
+            time.trim().to_string(),
+        ),
+        None => (int_ext, rest.to_string(), String::new()),
+    }
+}
+
+const CSV_HEADER: &[&str] = &[
+    "scene",
+    "scene_id",
+    "heading",
+    "int_ext",
+    "location",
+    "day_night",
+    "pages",
+    "scene_type",
+    "cast",
+    "extras",
+    "props",
+    "analyzed",
+];
+
+/// One row per scene; list cells are separated by "; "
+pub fn export_csv(sheets: &[BreakdownSheet]) -> String {
+    let mut csv = csv_line(CSV_HEADER.iter().map(|h| h.to_string()));
+    for sheet in sheets {
+        csv.push_str(&csv_line([
+            sheet.scene_number.clone(),
+            sheet.scene_id.clone(),
+            sheet.heading.clone(),
+            sheet.int_ext.clone(),
+            sheet.location.clone(),
+            sheet.day_night.clone(),
+            sheet.pages.clone(),
+            sheet.scene_type.clone(),
+            sheet.cast.join("; "),
+            sheet.extras.join("; "),
+            sheet.props.join("; "),
+            if sheet.analyzed { "yes" } else { "no" }.to_string(),
+        ]));
+    }
+    csv
+}
+
+const SHEET_CSS: &str = r#"
+.sheet { border: 2px solid #1f2328; padding: 16px; margin: 24px 0; page-break-after: always; }
+.sheet table { width: 100%; border-collapse: collapse; margin-bottom: 12px; }
+.sheet th, .sheet td { border: 1px solid #8c959f; padding: 6px 8px; text-align: left; vertical-align: top; }
+.sheet th { background: #f6f8fa; width: 20%; }
+.sheet ul { margin: 0; padding-left: 18px; }
+"#;
+
+/// Printable HTML with one breakdown sheet per page
+pub fn export_html(title: &str, sheets: &[BreakdownSheet]) -> String {
+    let mut body = format!(
+        "<style>{}</style>\n<h1>{}</h1>\n<p class=\"subtitle\">Scene breakdown sheets</p>\n",
+        SHEET_CSS,
+        escape(title)
+    );
+
+    for sheet in sheets {
+        body.push_str(&format!(
+            "<section class=\"sheet\" id=\"{}\">\n<h2>Scene {}: {}</h2>\n<table>\n",
+            report::anchor("scene", &sheet.scene_id),
+            escape(&sheet.scene_number),
+            escape(&sheet.heading)
+        ));
+        push_row(&mut body, "Int/Ext", &sheet.int_ext);
+        push_row(&mut body, "Location", &sheet.location);
+        push_row(&mut body, "Day/Night", &sheet.day_night);
+        push_row(&mut body, "Pages", &sheet.pages);
+        push_row(&mut body, "Scene type", &sheet.scene_type);
+        push_list_row(&mut body, "Cast", &sheet.cast);
+        push_list_row(&mut body, "Extras", &sheet.extras);
+        push_list_row(&mut body, "Props / visual elements", &sheet.props);
+        body.push_str("</table>\n");
+        if !sheet.analyzed {
+            body.push_str("<p class=\"empty\">Not analyzed; details from the scene list</p>\n");
+        }
+        body.push_str("</section>\n");
+    }
+
+    report::page(&format!("{} \u{2014} Breakdown Sheets", title), &body)
+}
+
+fn push_row(html: &mut String, label: &str, value: &str) {
+    html.push_str(&format!(
+        "<tr><th>{}</th><td>{}</td></tr>\n",
+        label,
+        escape(value)
+    ));
+}
+
+fn push_list_row(html: &mut String, label: &str, items: &[String]) {
+    html.push_str(&format!("<tr><th>{}</th><td>", label));
+    if !items.is_empty() {
+        html.push_str("<ul>");
+        for item in items {
+            html.push_str(&format!("<li>{}</li>", escape(item)));
+        }
+        html.push_str("</ul>");
+    }
+    html.push_str("</td></tr>\n");
+}
+
+/// Render sheets as "csv" or "html"
+pub fn render(title: &str, sheets: &[BreakdownSheet], format: &str) -> Result<String, String> {
+    match format {
+        "csv" => Ok(export_csv(sheets)),
+        "html" => Ok(export_html(title, sheets)),
+        _ => Err(format!("Unknown breakdown format: {}", format)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SceneAnalysis, SceneSummary};
+
+    fn scene(id: &str, slugline: &str, analysis: Option<SceneAnalysis>) -> SceneDetail {
+        SceneDetail {
+            summary: SceneSummary {
+                id: id.to_string(),
+                index: 4,
+                slugline: slugline.to_string(),
+                duration: "1:00".to_string(),
+                word_count: 80,
+                line_count: 10,
+                characters: vec!["MAYA".to_string(), "LEO".to_string()],
+            },
+            analysis,
+        }
+    }
+
+    #[test]
+    fn test_parse_slugline() {
+        assert_eq!(
+            parse_slugline("INT. KITCHEN - NIGHT"),
+            ("INT".into(), "KITCHEN".into(), "NIGHT".into())
+        );
+        assert_eq!(
+            parse_slugline("int./ext. CAR - MOVING - DAY"),
+            ("INT/EXT".into(), "CAR - MOVING".into(), "DAY".into())
+        );
+        assert_eq!(
+            parse_slugline("MONTAGE"),
+            (String::new(), "MONTAGE".into(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_sheet_prefers_analysis() {
+        let sheet = BreakdownSheet::from_scene(&scene(
+            "scn_004",
+            "EXT. ROOF - DAWN",
+            Some(SceneAnalysis {
+                scene_number: Some("4A".to_string()),
+                time_of_day: Some("Dawn".to_string()),
+                length_pages: Some("1 3/8".to_string()),
+                speaking_chars: vec!["MAYA".to_string()],
+                non_speaking_chars: vec!["GUARD".to_string()],
+                non_speaking_details: vec!["GUARD".to_string(), "Pigeons".to_string()],
+                visual_symbols: vec!["Duffel bag".to_string()],
+                ..Default::default()
+            }),
+        ));
+
+        assert_eq!(sheet.scene_number, "4A");
+        assert_eq!(sheet.int_ext, "EXT");
+        assert_eq!(sheet.location, "ROOF");
+        assert_eq!(sheet.day_night, "Dawn");
+        assert_eq!(sheet.pages, "1 3/8");
+        assert_eq!(sheet.cast, vec!["MAYA"]);
+        assert_eq!(sheet.extras, vec!["GUARD", "Pigeons"]);
+        assert_eq!(sheet.props, vec!["Duffel bag"]);
+    }
+
+    #[test]
+    fn test_sheet_falls_back_to_summary() {
+        let sheet = BreakdownSheet::from_scene(&scene("scn_004", "INT. VAULT - NIGHT", None));
+
+        assert_eq!(sheet.scene_number, "4");
+        assert_eq!(sheet.day_night, "NIGHT");
+        assert_eq!(sheet.cast, vec!["MAYA", "LEO"]);
+        assert!(sheet.extras.is_empty());
+        assert!(!sheet.analyzed);
+    }
+
+    #[test]
+    fn test_render_csv_and_html() {
+        let sheets = vec![BreakdownSheet::from_scene(&scene(
+            "scn_004",
+            "INT. VAULT - NIGHT",
+            None,
+        ))];
+
+        let csv = render("Heist", &sheets, "csv").unwrap();
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            "4,scn_004,INT. VAULT - NIGHT,INT,VAULT,NIGHT,,,MAYA; LEO,,,no"
+        );
+
+        let html = render("Heist", &sheets, "html").unwrap();
+        assert!(html.contains("<h2>Scene 4: INT. VAULT - NIGHT</h2>"));
+        assert!(html.contains("<tr><th>Cast</th><td><ul><li>MAYA</li><li>LEO</li></ul></td></tr>"));
+        assert!(html.contains("page-break-after: always"));
+
+        assert!(render("Heist", &sheets, "pdf").is_err());
+    }
+}
@@ -0,0 +1,556 @@
+// Project Bundle Service
+// Exports a KSPD project to a single portable zip archive with a checksummed
+// contents listing, and imports such archives into the projects root.
+//
+// Archive layout:
+//   bundle.json          BundleManifest (file list with sizes and SHA-256)
+//   project/<path>       project files, relative to the project directory
+
+use crate::services::{discovery, import};
+use crate::types::{BundleFile, BundleManifest, Project};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bump when the archive layout changes incompatibly
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "bundle.json";
+const PROJECT_PREFIX: &str = "project/";
+
+/// Saved analysis results (see dashboard::scan_analysis_index)
+const ANALYSIS_DIR: &str = "metadata/analysis";
+/// Story storage database; SQLite sidecar files share the prefix
+const STORAGE_DB: &str = "metadata/artifacts/story-storage.db";
+
+/// What to leave out of an exported bundle
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BundleOptions {
+    pub exclude_analysis: bool,
+    pub exclude_storage: bool,
+}
+
+impl BundleOptions {
+    fn excludes(&self, rel_path: &str) -> bool {
+        (self.exclude_analysis
+            && (rel_path == ANALYSIS_DIR || rel_path.starts_with(&format!("{}/", ANALYSIS_DIR))))
+            || (self.exclude_storage && rel_path.starts_with(STORAGE_DB))
+    }
+}
+
+/// Read wrapper that hashes and counts everything read through it
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            hasher: Sha256::new(),
+            bytes: 0,
+        }
+    }
+
+    fn finish(self) -> (u64, String) {
+        (self.bytes, format!("{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes += n as u64;
+        Ok(n)
+    }
+}
+
+/// Canonical form of `path`; a file that doesn't exist yet resolves through its parent
+fn resolved(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Export a project to a bundle archive at `output_path`
+pub fn export_bundle(
+    project_path: &Path,
+    output_path: &Path,
+    options: BundleOptions,
+) -> Result<BundleManifest, String> {
+    if !discovery::is_kspd(project_path) {
+        return Err(format!("Not a KSPD project: {}", project_path.display()));
+    }
+    if resolved(output_path).starts_with(resolved(project_path)) {
+        return Err("Bundle cannot be written inside the project it contains".to_string());
+    }
+
+    let project = discovery::read_project_metadata(project_path)?;
+    let mut rel_paths = Vec::new();
+    collect_files(project_path, project_path, &options, &mut rel_paths)?;
+    rel_paths.sort();
+
+    let mut manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        title: project.title,
+        source_name: project_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("project.kspd")
+            .to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+        includes_analysis: !options.exclude_analysis,
+        includes_storage: !options.exclude_storage,
+        files: Vec::new(),
+    };
+
+    // Write next to the destination and rename, so a failed export never
+    // leaves a truncated archive at output_path
+    let tmp_path = output_path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    let result = write_archive(project_path, &tmp_path, &rel_paths, &mut manifest)
+        .and_then(|_| fs::rename(&tmp_path, output_path).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to export bundle: {}", e));
+    }
+
+    tracing::info!(
+        "Exported {} ({} files) to {}",
+        manifest.title,
+        manifest.files.len(),
+        output_path.display()
+    );
+    Ok(manifest)
+}
+
+fn collect_files(
+    project_path: &Path,
+    dir: &Path,
+    options: &BundleOptions,
+    rel_paths: &mut Vec<String>,
+) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let rel = path
+            .strip_prefix(project_path)
+            .map_err(|e| e.to_string())?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if options.excludes(&rel) {
+            continue;
+        }
+
+        // Symlinks are not followed: a bundle only carries the project's own files
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(project_path, &path, options, rel_paths)?;
+        } else if file_type.is_file() {
+            rel_paths.push(rel);
+        }
+    }
+
+    Ok(())
+}
+
+fn write_archive(
+    project_path: &Path,
+    archive_path: &Path,
+    rel_paths: &[String],
+    manifest: &mut BundleManifest,
+) -> Result<(), String> {
+    let file = fs::File::create(archive_path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+
+    for rel in rel_paths {
+        let source = fs::File::open(project_path.join(rel))
+            .map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+        let size = source.metadata().map(|m| m.len()).unwrap_or(0);
+
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(size >= u32::MAX as u64);
+        zip.start_file(format!("{}{}", PROJECT_PREFIX, rel), options)
+            .map_err(|e| e.to_string())?;
+
+        let mut reader = HashingReader::new(source);
+        io::copy(&mut reader, &mut zip).map_err(|e| format!("Failed to add {}: {}", rel, e))?;
+        let (size, sha256) = reader.finish();
+
+        manifest.files.push(BundleFile {
+            path: rel.clone(),
+            size,
+            sha256,
+        });
+    }
+
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize bundle manifest: {}", e))?;
+    zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default())
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+
+    let file = zip.finish().map_err(|e| e.to_string())?;
+    file.sync_all().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Read and validate a bundle's contents listing without extracting it
+pub fn inspect_bundle(bundle_path: &Path) -> Result<BundleManifest, String> {
+    let mut archive = open_archive(bundle_path)?;
+    read_manifest(&mut archive)
+}
+
+/// Import a bundle into `projects_dir`. The project directory name is derived
+/// from `name` (e.g. a suggestion from check_import_collision) or the bundle
+/// title. Every file is verified against the bundle's checksums before the
+/// project appears in the projects root.
+pub fn import_bundle(
+    bundle_path: &Path,
+    projects_dir: &str,
+    name: Option<&str>,
+) -> Result<Project, String> {
+    let mut archive = open_archive(bundle_path)?;
+    let manifest = read_manifest(&mut archive)?;
+
+    let target = import::resolve_target_path(projects_dir, name.unwrap_or(&manifest.title))?;
+    if import::check_collision(&target) {
+        return Err(format!("Project already exists at {}", target));
+    }
+
+    fs::create_dir_all(projects_dir)
+        .map_err(|e| format!("Failed to create projects directory: {}", e))?;
+
+    // Claim the target as project creation does, so two imports can't both
+    // pass an exists-check and race to the rename
+    let target_path = Path::new(&target);
+    fs::create_dir(target_path).map_err(|e| match e.kind() {
+        io::ErrorKind::AlreadyExists => format!("Target path already exists: {}", target),
+        _ => format!("Failed to create project directory: {}", e),
+    })?;
+
+    let staging = target_path.with_file_name(format!(".import.{}.tmp", uuid::Uuid::new_v4()));
+    let result = extract_verified(&mut archive, &manifest, &staging).and_then(|_| {
+        // The bundle's own description of itself is not trusted; the
+        // extracted files must form a project
+        if !staging.join("manifest.json").is_file() {
+            return Err("Bundle does not contain a KSPD project (no manifest.json)".to_string());
+        }
+        fs::rename(&staging, target_path).map_err(|e| format!("Failed to import project: {}", e))
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&staging);
+        let _ = fs::remove_dir(target_path);
+        return Err(e);
+    }
+
+    tracing::info!("Imported bundle {} to {}", bundle_path.display(), target);
+    discovery::read_project_metadata(target_path)
+}
+
+fn open_archive(bundle_path: &Path) -> Result<ZipArchive<fs::File>, String> {
+    let file = fs::File::open(bundle_path).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    ZipArchive::new(file).map_err(|e| format!("Invalid bundle archive: {}", e))
+}
+
+fn read_manifest(archive: &mut ZipArchive<fs::File>) -> Result<BundleManifest, String> {
+    let mut content = String::new();
+    archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| "Bundle is missing bundle.json".to_string())?
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read bundle.json: {}", e))?;
+
+    let manifest: BundleManifest =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid bundle.json: {}", e))?;
+
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format version {} is newer than supported ({})",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+    if let Some(bad) = manifest
+        .files
+        .iter()
+        .find(|f| safe_relative_path(&f.path).is_none())
+    {
+        return Err(format!("Bundle contains an unsafe path: {}", bad.path));
+    }
+
+    Ok(manifest)
+}
+
+/// Accept only plain relative paths (no root, no "..", no ".")
+fn safe_relative_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let safe = !path.as_os_str().is_empty()
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    safe.then(|| path.to_path_buf())
+}
+
+fn extract_verified(
+    archive: &mut ZipArchive<fs::File>,
+    manifest: &BundleManifest,
+    dest: &Path,
+) -> Result<(), String> {
+    // The archive must hold exactly the listed files
+    let listed: HashSet<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    for entry_name in archive.file_names() {
+        if entry_name == MANIFEST_ENTRY || entry_name.ends_with('/') {
+            continue;
+        }
+        let listed_entry = entry_name
+            .strip_prefix(PROJECT_PREFIX)
+            .map(|rel| listed.contains(rel))
+            .unwrap_or(false);
+        if !listed_entry {
+            return Err(format!("Bundle contains an unlisted file: {}", entry_name));
+        }
+    }
+
+    fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+
+    for file in &manifest.files {
+        let rel = safe_relative_path(&file.path)
+            .ok_or_else(|| format!("Bundle contains an unsafe path: {}", file.path))?;
+        let out_path = dest.join(rel);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        let entry = archive
+            .by_name(&format!("{}{}", PROJECT_PREFIX, file.path))
+            .map_err(|_| format!("Bundle is missing {}", file.path))?;
+        // Read at most one byte past the declared size so an oversized entry stops early
+        let mut reader = HashingReader::new(entry.take(file.size.saturating_add(1)));
+        let mut out = fs::File::create(&out_path)
+            .map_err(|e| format!("Failed to write {}: {}", file.path, e))?;
+        io::copy(&mut reader, &mut out)
+            .map_err(|e| format!("Failed to extract {}: {}", file.path, e))?;
+
+        let (size, sha256) = reader.finish();
+        if size > file.size {
+            return Err(format!(
+                "Bundle entry is larger than declared: {}",
+                file.path
+            ));
+        }
+        if size != file.size || sha256 != file.sha256 {
+            return Err(format!("Checksum mismatch for {}", file.path));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_project(base: &Path) -> PathBuf {
+        let project = base.join("heist.kspd");
+        fs::create_dir_all(project.join("metadata/analysis")).unwrap();
+        fs::create_dir_all(project.join("metadata/artifacts")).unwrap();
+        fs::write(project.join("manifest.json"), r#"{"title": "The Heist"}"#).unwrap();
+        fs::write(project.join("metadata/scenes.json"), "[{}, {}]").unwrap();
+        fs::write(project.join("metadata/analysis/scn_001.json"), "{}").unwrap();
+        fs::write(project.join("metadata/artifacts/story-storage.db"), "db").unwrap();
+        project
+    }
+
+    fn temp_base(name: &str) -> PathBuf {
+        let base = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        base
+    }
+
+    fn paths(manifest: &BundleManifest) -> Vec<&str> {
+        manifest.files.iter().map(|f| f.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_export_and_import_roundtrip() {
+        let base = temp_base("khaos_bundle_roundtrip");
+        let project = setup_project(&base);
+        let bundle = base.join("heist.kspd.zip");
+
+        let exported = export_bundle(&project, &bundle, BundleOptions::default()).unwrap();
+        assert_eq!(exported.title, "The Heist");
+        assert_eq!(exported.files.len(), 4);
+        assert_eq!(inspect_bundle(&bundle).unwrap(), exported);
+
+        let root = base.join("imported");
+        let imported = import_bundle(&bundle, root.to_str().unwrap(), None).unwrap();
+        assert_eq!(imported.title, "The Heist");
+        assert_eq!(imported.scene_count, 2);
+        assert!(imported.ingested);
+        assert!(root
+            .join("The_Heist.kspd/metadata/analysis/scn_001.json")
+            .is_file());
+
+        // Importing again collides with the first import
+        let err = import_bundle(&bundle, root.to_str().unwrap(), None).unwrap_err();
+        assert!(err.contains("already exists"));
+        assert!(import_bundle(&bundle, root.to_str().unwrap(), Some("The_Heist_1")).is_ok());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_export_excludes_analysis_and_storage() {
+        let base = temp_base("khaos_bundle_exclude");
+        let project = setup_project(&base);
+        let bundle = base.join("heist.kspd.zip");
+
+        let options = BundleOptions {
+            exclude_analysis: true,
+            exclude_storage: true,
+        };
+        let manifest = export_bundle(&project, &bundle, options).unwrap();
+
+        assert_eq!(
+            paths(&manifest),
+            vec!["manifest.json", "metadata/scenes.json"]
+        );
+        assert!(!manifest.includes_analysis);
+        assert!(!manifest.includes_storage);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_bundle() {
+        let base = temp_base("khaos_bundle_tampered");
+        let project = setup_project(&base);
+        let bundle = base.join("heist.kspd.zip");
+        let manifest = export_bundle(&project, &bundle, BundleOptions::default()).unwrap();
+
+        // Rewrite the archive with a file whose contents no longer match its checksum
+        let tampered = base.join("tampered.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&tampered).unwrap());
+        for file in &manifest.files {
+            zip.start_file(
+                format!("{}{}", PROJECT_PREFIX, file.path),
+                SimpleFileOptions::default(),
+            )
+            .unwrap();
+            zip.write_all(b"changed").unwrap();
+        }
+        zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let root = base.join("imported");
+        let err = import_bundle(&tampered, root.to_str().unwrap(), None).unwrap_err();
+        assert!(err.starts_with("Checksum mismatch"), "{}", err);
+        // Nothing is left behind in the projects root
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_import_requires_project_manifest() {
+        let base = temp_base("khaos_bundle_no_manifest");
+        let project = setup_project(&base);
+        fs::remove_file(project.join("manifest.json")).unwrap();
+        let bundle = base.join("heist.kspd.zip");
+
+        // The bundle claims a .kspd source, but the files aren't a project
+        let manifest = export_bundle(&project, &bundle, BundleOptions::default()).unwrap();
+        assert_eq!(manifest.source_name, "heist.kspd");
+
+        let root = base.join("imported");
+        let err = import_bundle(&bundle, root.to_str().unwrap(), None).unwrap_err();
+        assert!(err.contains("no manifest.json"), "{}", err);
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_import_rejects_oversized_entry() {
+        let base = temp_base("khaos_bundle_oversized");
+        let project = setup_project(&base);
+        let bundle = base.join("heist.kspd.zip");
+        let manifest = export_bundle(&project, &bundle, BundleOptions::default()).unwrap();
+
+        // Same manifest, but every entry carries more bytes than it declares
+        let oversized = base.join("oversized.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&oversized).unwrap());
+        for file in &manifest.files {
+            zip.start_file(
+                format!("{}{}", PROJECT_PREFIX, file.path),
+                SimpleFileOptions::default(),
+            )
+            .unwrap();
+            let padded = vec![b'x'; file.size as usize + 4096];
+            zip.write_all(&padded).unwrap();
+        }
+        zip.start_file(MANIFEST_ENTRY, SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let root = base.join("imported");
+        let err = import_bundle(&oversized, root.to_str().unwrap(), None).unwrap_err();
+        let capped = err.starts_with("Bundle entry is larger than declared");
+        assert!(capped, "{}", err);
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_export_rejects_output_inside_project_via_dotdot() {
+        let base = temp_base("khaos_bundle_inside");
+        let project = setup_project(&base);
+        fs::create_dir_all(base.join("other")).unwrap();
+
+        // The two paths only share a prefix once ".." is resolved
+        let indirect = base.join("other").join("..").join("heist.kspd");
+        let output = project.join("heist.kspd.zip");
+        let err = export_bundle(&indirect, &output, BundleOptions::default()).unwrap_err();
+        assert!(err.contains("inside the project"), "{}", err);
+        assert!(!output.exists());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert!(safe_relative_path("metadata/scenes.json").is_some());
+        assert!(safe_relative_path("../escape").is_none());
+        assert!(safe_relative_path("/etc/passwd").is_none());
+        assert!(safe_relative_path("").is_none());
+    }
+}
@@ -0,0 +1,578 @@
+// Compare Service
+// Aligns scenes, characters and locations between two KSPD projects (e.g. two
+// drafts) and reports what was added, removed, moved or changed
+
+use crate::services::export::{self, ExportData, ExportScope};
+use crate::types::{
+    ChangeKind, CharacterDetail, EntityChange, FieldChange, LocationDetail, ProjectComparison,
+    SceneChange, SceneDetail,
+};
+use serde::Serialize;
+
+/// Load both projects with their saved analyses and compare them
+pub async fn compare_projects(
+    base_path: &str,
+    other_path: &str,
+) -> Result<ProjectComparison, String> {
+    let base = export::load_export_data(base_path, ExportScope::All).await?;
+    let other = export::load_export_data(other_path, ExportScope::All).await?;
+    Ok(compare(&base, &other))
+}
+
+pub fn compare(base: &ExportData, other: &ExportData) -> ProjectComparison {
+    ProjectComparison {
+        base_title: base.title.clone(),
+        base_path: base.project_path.clone(),
+        other_title: other.title.clone(),
+        other_path: other.project_path.clone(),
+        scenes: compare_scenes(
+            base.scenes.as_deref().unwrap_or_default(),
+            other.scenes.as_deref().unwrap_or_default(),
+        ),
+        characters: compare_characters(
+            base.characters.as_deref().unwrap_or_default(),
+            other.characters.as_deref().unwrap_or_default(),
+        ),
+        locations: compare_locations(
+            base.locations.as_deref().unwrap_or_default(),
+            other.locations.as_deref().unwrap_or_default(),
+        ),
+    }
+}
+
+/// Sluglines and names are compared case-insensitively with whitespace collapsed
+fn align_key(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+/// Pair up items with equal keys. Repeated keys (e.g. the same location
+/// revisited) are paired in order of appearance.
+fn align(base: &[String], other: &[String]) -> Vec<(usize, usize)> {
+    let mut used = vec![false; other.len()];
+    let mut pairs = Vec::new();
+    for (b, key) in base.iter().enumerate() {
+        if let Some(o) = (0..other.len()).find(|&o| !used[o] && other[o] == *key) {
+            used[o] = true;
+            pairs.push((b, o));
+        }
+    }
+    pairs
+}
+
+/// Indices into `pairs` that keep their relative order (longest increasing
+/// subsequence of other-positions); the remaining pairs were moved
+fn in_order(pairs: &[(usize, usize)]) -> Vec<bool> {
+    let n = pairs.len();
+    let mut length = vec![1usize; n];
+    let mut prev = vec![None; n];
+    for i in 0..n {
+        for j in 0..i {
+            if pairs[j].1 < pairs[i].1 && length[j] + 1 > length[i] {
+                length[i] = length[j] + 1;
+                prev[i] = Some(j);
+            }
+        }
+    }
+
+    let mut keep = vec![false; n];
+    let mut current = (0..n).max_by_key(|&i| length[i]);
+    while let Some(i) = current {
+        keep[i] = true;
+        current = prev[i];
+    }
+    keep
+}
+
+/// Scene changes in the other project's order, followed by removed scenes in
+/// the base project's order
+fn compare_scenes(base: &[SceneDetail], other: &[SceneDetail]) -> Vec<SceneChange> {
+    let mut base: Vec<&SceneDetail> = base.iter().collect();
+    let mut other: Vec<&SceneDetail> = other.iter().collect();
+    base.sort_by_key(|scene| scene.summary.index);
+    other.sort_by_key(|scene| scene.summary.index);
+
+    let keys = |scenes: &[&SceneDetail]| -> Vec<String> {
+        scenes
+            .iter()
+            .map(|s| align_key(&s.summary.slugline))
+            .collect()
+    };
+    let pairs = align(&keys(&base), &keys(&other));
+    let keep = in_order(&pairs);
+
+    let mut matched_base = vec![None; base.len()];
+    let mut matched_other = vec![None; other.len()];
+    for (i, &(b, o)) in pairs.iter().enumerate() {
+        matched_base[b] = Some(o);
+        matched_other[o] = Some((b, keep[i]));
+    }
+
+    let mut changes = Vec::new();
+    for (o, scene) in other.iter().enumerate() {
+        let change = match matched_other[o] {
+            None => SceneChange {
+                kind: ChangeKind::Added,
+                slugline: scene.summary.slugline.clone(),
+                base_index: None,
+                other_index: Some(scene.summary.index),
+                changes: Vec::new(),
+            },
+            Some((b, in_order)) => {
+                let fields = analysis_changes(base[b].analysis.as_ref(), scene.analysis.as_ref());
+                let kind = if !in_order {
+                    ChangeKind::Moved
+                } else if !fields.is_empty() {
+                    ChangeKind::Modified
+                } else {
+                    continue;
+                };
+                SceneChange {
+                    kind,
+                    slugline: scene.summary.slugline.clone(),
+                    base_index: Some(base[b].summary.index),
+                    other_index: Some(scene.summary.index),
+                    changes: fields,
+                }
+            }
+        };
+        changes.push(change);
+    }
+
+    for (b, scene) in base.iter().enumerate() {
+        if matched_base[b].is_none() {
+            changes.push(SceneChange {
+                kind: ChangeKind::Removed,
+                slugline: scene.summary.slugline.clone(),
+                base_index: Some(scene.summary.index),
+                other_index: None,
+                changes: Vec::new(),
+            });
+        }
+    }
+
+    changes
+}
+
+fn compare_characters(base: &[CharacterDetail], other: &[CharacterDetail]) -> Vec<EntityChange> {
+    compare_entities(
+        base,
+        other,
+        |c| &c.summary.name,
+        |before, after| {
+            let mut changes = Vec::new();
+            // Shares are shown to one decimal place, so smaller shifts are noise
+            if (before.summary.percentage - after.summary.percentage).abs() >= 0.05 {
+                changes.push(FieldChange {
+                    field: "dialogue_share".to_string(),
+                    before: Some(format!("{:.1}%", before.summary.percentage)),
+                    after: Some(format!("{:.1}%", after.summary.percentage)),
+                });
+            }
+            changes.extend(analysis_changes(
+                before.analysis.as_ref(),
+                after.analysis.as_ref(),
+            ));
+            changes
+        },
+    )
+}
+
+fn compare_locations(base: &[LocationDetail], other: &[LocationDetail]) -> Vec<EntityChange> {
+    compare_entities(
+        base,
+        other,
+        |l| &l.summary.name,
+        |before, after| analysis_changes(before.analysis.as_ref(), after.analysis.as_ref()),
+    )
+}
+
+/// Align entities by name; changes follow the other project's order, then
+/// removed entities in the base project's order
+fn compare_entities<T>(
+    base: &[T],
+    other: &[T],
+    name: impl Fn(&T) -> &String,
+    diff: impl Fn(&T, &T) -> Vec<FieldChange>,
+) -> Vec<EntityChange> {
+    let keys = |items: &[T]| -> Vec<String> { items.iter().map(|i| align_key(name(i))).collect() };
+    let pairs = align(&keys(base), &keys(other));
+
+    let mut changes = Vec::new();
+    for (o, item) in other.iter().enumerate() {
+        match pairs.iter().find(|(_, po)| *po == o) {
+            None => changes.push(EntityChange {
+                kind: ChangeKind::Added,
+                name: name(item).clone(),
+                changes: Vec::new(),
+            }),
+            Some(&(b, _)) => {
+                let fields = diff(&base[b], item);
+                if !fields.is_empty() {
+                    changes.push(EntityChange {
+                        kind: ChangeKind::Modified,
+                        name: name(item).clone(),
+                        changes: fields,
+                    });
+                }
+            }
+        }
+    }
+
+    for (b, item) in base.iter().enumerate() {
+        if !pairs.iter().any(|(pb, _)| *pb == b) {
+            changes.push(EntityChange {
+                kind: ChangeKind::Removed,
+                name: name(item).clone(),
+                changes: Vec::new(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Field-by-field differences between two saved analyses. When only one side
+/// is analyzed, a single "analyzed" change is reported instead.
+fn analysis_changes<T: Serialize>(before: Option<&T>, after: Option<&T>) -> Vec<FieldChange> {
+    let (before, after) = match (before, after) {
+        (None, None) => return Vec::new(),
+        (Some(before), Some(after)) => (before, after),
+        (before, _) => {
+            let (was, now) = if before.is_some() {
+                ("yes", "no")
+            } else {
+                ("no", "yes")
+            };
+            return vec![FieldChange {
+                field: "analyzed".to_string(),
+                before: Some(was.to_string()),
+                after: Some(now.to_string()),
+            }];
+        }
+    };
+
+    let (Ok(serde_json::Value::Object(before)), Ok(serde_json::Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+    for (field, value) in &before {
+        let was = display_value(value);
+        let now = after.get(field).and_then(display_value);
+        if was != now {
+            changes.push(FieldChange {
+                field: field.clone(),
+                before: was,
+                after: now,
+            });
+        }
+    }
+    changes
+}
+
+/// Text form of an analysis value; empty strings and lists count as absent
+fn display_value(value: &serde_json::Value) -> Option<String> {
+    let text = match value {
+        serde_json::Value::Null => return None,
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("; "),
+        other => other.to_string(),
+    };
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Render a comparison as "json" or "markdown"
+pub fn render(comparison: &ProjectComparison, format: &str) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(comparison)
+            .map_err(|e| format!("Failed to serialize comparison: {}", e)),
+        "markdown" | "md" => Ok(export_markdown(comparison)),
+        _ => Err(format!("Unknown comparison format: {}", format)),
+    }
+}
+
+pub fn export_markdown(comparison: &ProjectComparison) -> String {
+    let mut md = format!(
+        "# {} \u{2192} {}\n\nComparing `{}` with `{}`\n",
+        comparison.base_title, comparison.other_title, comparison.base_path, comparison.other_path
+    );
+
+    md.push_str("\n## Scenes\n\n");
+    let kinds: Vec<ChangeKind> = comparison.scenes.iter().map(|c| c.kind).collect();
+    md.push_str(&counts_line(&kinds));
+    for change in &comparison.scenes {
+        let position = match (change.base_index, change.other_index) {
+            (Some(b), Some(o)) if b != o => format!("scene {} \u{2192} {}", b, o),
+            (_, Some(o)) => format!("scene {}", o),
+            (Some(b), None) => format!("was scene {}", b),
+            (None, None) => String::new(),
+        };
+        md.push_str(&format!(
+            "- **{}:** {} ({})\n",
+            kind_label(change.kind),
+            change.slugline,
+            position
+        ));
+        push_field_changes(&mut md, &change.changes);
+    }
+
+    for (title, entities) in [
+        ("Characters", &comparison.characters),
+        ("Locations", &comparison.locations),
+    ] {
+        md.push_str(&format!("\n## {}\n\n", title));
+        let kinds: Vec<ChangeKind> = entities.iter().map(|c| c.kind).collect();
+        md.push_str(&counts_line(&kinds));
+        for change in entities {
+            md.push_str(&format!(
+                "- **{}:** {}\n",
+                kind_label(change.kind),
+                change.name
+            ));
+            push_field_changes(&mut md, &change.changes);
+        }
+    }
+
+    md
+}
+
+fn kind_label(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "Added",
+        ChangeKind::Removed => "Removed",
+        ChangeKind::Moved => "Moved",
+        ChangeKind::Modified => "Modified",
+    }
+}
+
+/// "2 added, 1 removed" summary line, or "_No differences_"
+fn counts_line(kinds: &[ChangeKind]) -> String {
+    let counts: Vec<String> = [
+        ChangeKind::Added,
+        ChangeKind::Removed,
+        ChangeKind::Moved,
+        ChangeKind::Modified,
+    ]
+    .iter()
+    .filter_map(|kind| {
+        let count = kinds.iter().filter(|k| *k == kind).count();
+        (count > 0).then(|| format!("{} {}", count, kind_label(*kind).to_lowercase()))
+    })
+    .collect();
+
+    if counts.is_empty() {
+        "_No differences_\n".to_string()
+    } else {
+        format!("{}\n\n", counts.join(", "))
+    }
+}
+
+fn push_field_changes(md: &mut String, changes: &[FieldChange]) {
+    for change in changes {
+        md.push_str(&format!(
+            "  - `{}`: {} \u{2192} {}\n",
+            change.field,
+            change.before.as_deref().unwrap_or("_none_"),
+            change.after.as_deref().unwrap_or("_none_")
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CharacterAnalysis, CharacterSummary, SceneAnalysis, SceneSummary};
+
+    fn scene(index: usize, slugline: &str, summary: Option<&str>) -> SceneDetail {
+        SceneDetail {
+            summary: SceneSummary {
+                id: format!("scn_{:03}", index),
+                index,
+                slugline: slugline.to_string(),
+                duration: String::new(),
+                word_count: 0,
+                line_count: 0,
+                characters: Vec::new(),
+            },
+            analysis: summary.map(|s| SceneAnalysis {
+                summary: Some(s.to_string()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn character(name: &str, percentage: f64, arc: Option<&str>) -> CharacterDetail {
+        CharacterDetail {
+            summary: CharacterSummary {
+                id: name.to_lowercase(),
+                name: name.to_string(),
+                dialogue_lines: 0,
+                words: 0,
+                scene_count: 0,
+                percentage,
+            },
+            analysis: arc.map(|arc| CharacterAnalysis {
+                arc: Some(arc.to_string()),
+                themes: vec!["trust".to_string()],
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn data(title: &str, scenes: Vec<SceneDetail>, characters: Vec<CharacterDetail>) -> ExportData {
+        ExportData {
+            title: title.to_string(),
+            project_path: format!("/p/{}.kspd", title),
+            scope: ExportScope::All,
+            scenes: Some(scenes),
+            characters: Some(characters),
+            locations: Some(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_compare_scenes_added_removed_moved_modified() {
+        let base = data(
+            "Draft 1",
+            vec![
+                scene(1, "INT. VAULT - NIGHT", Some("Maya opens the vault")),
+                scene(2, "EXT. ROOF - DAWN", None),
+                scene(3, "INT. CAR - DAY", None),
+                scene(4, "INT. BANK - DAY", None),
+            ],
+            Vec::new(),
+        );
+        let other = data(
+            "Draft 2",
+            vec![
+                scene(
+                    1,
+                    "int.  vault - night",
+                    Some("Maya fails to open the vault"),
+                ),
+                scene(2, "INT. CAR - DAY", None),
+                scene(3, "EXT. ROOF - DAWN", None),
+                scene(4, "EXT. ALLEY - NIGHT", None),
+            ],
+            Vec::new(),
+        );
+
+        let scenes = compare(&base, &other).scenes;
+        let kinds: Vec<(ChangeKind, &str)> = scenes
+            .iter()
+            .map(|c| (c.kind, c.slugline.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ChangeKind::Modified, "int.  vault - night"),
+                (ChangeKind::Moved, "EXT. ROOF - DAWN"),
+                (ChangeKind::Added, "EXT. ALLEY - NIGHT"),
+                (ChangeKind::Removed, "INT. BANK - DAY"),
+            ]
+        );
+        assert_eq!(
+            scenes[0].changes,
+            vec![FieldChange {
+                field: "summary".to_string(),
+                before: Some("Maya opens the vault".to_string()),
+                after: Some("Maya fails to open the vault".to_string()),
+            }]
+        );
+        assert_eq!(
+            (scenes[1].base_index, scenes[1].other_index),
+            (Some(2), Some(3))
+        );
+    }
+
+    #[test]
+    fn test_compare_characters_dialogue_share_and_analysis() {
+        let base = data(
+            "Draft 1",
+            Vec::new(),
+            vec![
+                character("MAYA", 41.5, Some("Loner to leader")),
+                character("LEO", 20.0, None),
+                character("GUARD", 2.0, None),
+            ],
+        );
+        let other = data(
+            "Draft 2",
+            Vec::new(),
+            vec![
+                character("Maya", 45.25, Some("Leader to loner")),
+                character("LEO", 20.01, Some("Steady")),
+                character("VERA", 8.0, None),
+            ],
+        );
+
+        let characters = compare(&base, &other).characters;
+        assert_eq!(characters.len(), 4);
+
+        assert_eq!(characters[0].kind, ChangeKind::Modified);
+        let fields: Vec<&str> = characters[0]
+            .changes
+            .iter()
+            .map(|c| c.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["dialogue_share", "arc"]);
+        assert_eq!(characters[0].changes[0].after.as_deref(), Some("45.2%"));
+
+        // A negligible share change is ignored; the new analysis is not
+        assert_eq!(characters[1].changes.len(), 1);
+        assert_eq!(characters[1].changes[0].field, "analyzed");
+
+        assert_eq!(
+            (characters[2].kind, characters[2].name.as_str()),
+            (ChangeKind::Added, "VERA")
+        );
+        assert_eq!(
+            (characters[3].kind, characters[3].name.as_str()),
+            (ChangeKind::Removed, "GUARD")
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_and_json() {
+        let base = data(
+            "Draft 1",
+            vec![scene(1, "INT. VAULT - NIGHT", None)],
+            Vec::new(),
+        );
+        let other = data(
+            "Draft 2",
+            vec![
+                scene(1, "EXT. ALLEY - NIGHT", None),
+                scene(2, "INT. VAULT - NIGHT", None),
+            ],
+            Vec::new(),
+        );
+        let comparison = compare(&base, &other);
+
+        let md = render(&comparison, "markdown").unwrap();
+        assert!(md.starts_with("# Draft 1 \u{2192} Draft 2\n"));
+        assert!(md.contains("## Scenes\n\n1 added\n\n- **Added:** EXT. ALLEY - NIGHT (scene 1)\n"));
+        assert!(md.contains("## Characters\n\n_No differences_\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&comparison, "json").unwrap()).unwrap();
+        assert_eq!(json["scenes"][0]["kind"], "added");
+        assert!(render(&comparison, "pdf").is_err());
+    }
+}
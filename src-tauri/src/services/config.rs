@@ -1,69 +1,493 @@
 // Configuration Service
-// Manages persistent app settings at ~/.khaos-ui/config.json
+// Single home for app configuration: settings, project history and UI state in
+// $XDG_CONFIG_HOME/khaos-ui/config.json (default ~/.config/khaos-ui/config.json)
+//
+// Values that can come from several places (projects root, binary paths) are
+// resolved in one order:
+//   1. Environment variable (KHAOS_PROJECTS_ROOT, KHAOS_TOOLS_PATH, KHAOS_WFL_PATH)
+//   2. This config file
+//   3. khaos-tui's config ($XDG_CONFIG_HOME/khaos-tui/config.json), read-only
+//   4. Built-in default ($HOME/Projects, binaries on PATH)
+//
+// Earlier versions kept settings in ~/.khaos-ui/config.json; a config file
+// without `schema_version` is migrated by merging those files in.
+//
+// Several windows and khaos-tui share the file, so every write is a locked
+// read-modify-write (`update_config`) that lands through a temp file + rename.
 
-use crate::types::SettingsConfig;
+use crate::types::{SettingsConfig, MAX_RECENT_PROJECTS_LIMIT};
+use fs2::FileExt;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
 
-/// Get the config directory path (~/.khaos-ui/)
-fn config_dir() -> Result<PathBuf, String> {
-    let home =
-        std::env::var("HOME").map_err(|_| "HOME environment variable not set".to_string())?;
-    Ok(PathBuf::from(home).join(".khaos-ui"))
+/// Current config file schema
+pub const SCHEMA_VERSION: u64 = 1;
+
+const CONFIG_FILE: &str = "config.json";
+
+/// How long a write waits for another writer to release a file lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const LOCK_POLL: Duration = Duration::from_millis(20);
+
+/// Read-modify-write attempts before giving up on a file that keeps changing
+const WRITE_ATTEMPTS: u32 = 3;
+
+/// The config file as a JSON object (settings fields plus UI state keys)
+pub type ConfigMap = serde_json::Map<String, serde_json::Value>;
+
+fn home_dir() -> Result<PathBuf, String> {
+    std::env::var("HOME")
+        .map(PathBuf::from)
+        .map_err(|_| "HOME environment variable not set".to_string())
 }
 
-/// Get the config file path (~/.khaos-ui/config.json)
-fn config_path() -> Result<PathBuf, String> {
-    Ok(config_dir()?.join("config.json"))
+/// $XDG_CONFIG_HOME, or ~/.config when unset or not absolute
+fn xdg_config_home() -> Result<PathBuf, String> {
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if Path::new(&dir).is_absolute() => Ok(PathBuf::from(dir)),
+        _ => Ok(home_dir()?.join(".config")),
+    }
 }
 
-/// Load settings from disk, falling back to defaults if file doesn't exist.
-pub fn load_settings() -> Result<SettingsConfig, String> {
-    let path = config_path()?;
+/// Get the config directory path ($XDG_CONFIG_HOME/khaos-ui/)
+pub(crate) fn config_dir() -> Result<PathBuf, String> {
+    Ok(xdg_config_home()?.join("khaos-ui"))
+}
+
+/// Get the config file path ($XDG_CONFIG_HOME/khaos-ui/config.json)
+pub(crate) fn config_path() -> Result<PathBuf, String> {
+    Ok(config_dir()?.join(CONFIG_FILE))
+}
+
+/// Directory used by versions before the XDG layout (~/.khaos-ui/)
+fn legacy_dir() -> Result<PathBuf, String> {
+    Ok(home_dir()?.join(".khaos-ui"))
+}
 
-    if !path.exists() {
-        tracing::info!("No config file found at {:?}, using defaults", path);
-        return Ok(SettingsConfig::default());
+/// Config files merged in by the migration, highest priority first: the
+/// discovery config at the default XDG path (when XDG_CONFIG_HOME moves the
+/// config elsewhere), then the old settings file
+fn legacy_config_paths() -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    let default_path = home_dir()?
+        .join(".config")
+        .join("khaos-ui")
+        .join(CONFIG_FILE);
+    if default_path != config_path()? {
+        paths.push(default_path);
     }
+    paths.push(legacy_dir()?.join(CONFIG_FILE));
+    Ok(paths)
+}
 
-    let content =
-        fs::read_to_string(&path).map_err(|e| format!("Failed to read config file: {}", e))?;
+/// Path of a file kept next to the config (e.g. the secret store), moving it
+/// from the legacy directory the first time
+pub(crate) fn data_file(name: &str) -> Result<PathBuf, String> {
+    let path = config_dir()?.join(name);
+    let legacy = legacy_dir()?.join(name);
 
-    let config: SettingsConfig =
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))?;
+    if !path.exists() && legacy.is_file() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        fs::rename(&legacy, &path)
+            .or_else(|_| fs::copy(&legacy, &path).map(|_| ()))
+            .map_err(|e| format!("Failed to move {} to {:?}: {}", name, path, e))?;
+        tracing::info!("Moved {:?} to {:?}", legacy, path);
+    }
 
-    tracing::info!("Loaded settings from {:?}", path);
-    Ok(config)
+    Ok(path)
 }
 
-/// Save settings to disk, creating the directory if needed.
-pub fn save_settings(config: &SettingsConfig) -> Result<(), String> {
-    let dir = config_dir()?;
-    if !dir.exists() {
-        fs::create_dir_all(&dir)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+fn read_raw(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read config file {:?}: {}", path, e)),
+    }
+}
+
+fn parse_map(path: &Path, content: &str) -> Result<ConfigMap, String> {
+    serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse config file {:?}: {}", path, e))
+}
+
+fn read_map(path: &Path) -> Result<Option<ConfigMap>, String> {
+    read_raw(path)?
+        .map(|content| parse_map(path, &content))
+        .transpose()
+}
+
+/// Write via a temporary file in the same directory and rename it into place,
+/// so readers never observe a partially written file
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid file name")?;
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, path));
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to write {}: {}", file_name, e));
+    }
+
+    Ok(())
+}
+
+fn write_map(path: &Path, config: &ConfigMap) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
     }
 
-    let path = config_path()?;
     let content = serde_json::to_string_pretty(config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    write_atomic(path, content.as_bytes())
+}
+
+/// Exclusive advisory lock on a file written with `write_atomic`, released
+/// when dropped. The lock is taken on a sibling `.lock` file because writes
+/// replace the file itself.
+pub(crate) struct FileLock(fs::File);
+
+impl FileLock {
+    pub(crate) fn acquire(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        }
+
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or("Invalid file name")?;
+        let lock_path = path.with_file_name(format!("{}.lock", file_name));
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("Failed to open lock file {:?}: {}", lock_path, e))?;
+
+        let started = Instant::now();
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => return Ok(Self(file)),
+                Err(_) if started.elapsed() < LOCK_TIMEOUT => thread::sleep(LOCK_POLL),
+                Err(e) => return Err(format!("Failed to lock {}: {}", file_name, e)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.0);
+    }
+}
+
+/// Run a locked config update from async code. Waiting for the file lock
+/// sleeps the thread for up to LOCK_TIMEOUT, so it runs on the blocking pool
+/// instead of a runtime worker.
+pub async fn blocking<T, F>(update: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(update)
+        .await
+        .map_err(|e| format!("Failed to update config: {}", e))?
+}
+
+fn schema_version(config: &ConfigMap) -> u64 {
+    config
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+/// Merge legacy files into an unversioned config. Keys already present win,
+/// then earlier files in `legacy`; nulls are skipped. Returns whether any
+/// legacy file was found.
+fn migrate(config: &mut ConfigMap, legacy: &[PathBuf]) -> bool {
+    let mut merged = false;
+
+    for path in legacy {
+        match read_map(path) {
+            Ok(Some(old)) => {
+                for (key, value) in old {
+                    let taken = config.get(&key).is_some_and(|v| !v.is_null());
+                    if !taken && !value.is_null() {
+                        config.insert(key, value);
+                    }
+                }
+                merged = true;
+                tracing::info!("Migrated config from {:?}", path);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Skipping legacy config: {}", e),
+        }
+    }
+
+    config.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+    merged
+}
+
+/// Parse the file contents, migrating an unversioned config. Returns the
+/// config and whether the migration produced something worth writing.
+fn parse_config(
+    path: &Path,
+    content: Option<&str>,
+    legacy: &[PathBuf],
+) -> Result<(ConfigMap, bool), String> {
+    let found = content.is_some();
+    let mut config = match content {
+        Some(content) => parse_map(path, content)?,
+        None => ConfigMap::new(),
+    };
+
+    let version = schema_version(&config);
+    if version >= SCHEMA_VERSION {
+        if version > SCHEMA_VERSION {
+            tracing::warn!(
+                "Config {:?} has schema version {} (this build knows {})",
+                path,
+                version,
+                SCHEMA_VERSION
+            );
+        }
+        return Ok((config, false));
+    }
+
+    let migrated = migrate(&mut config, legacy) || found;
+    Ok((config, migrated))
+}
+
+fn load_from(path: &Path, legacy: &[PathBuf]) -> Result<ConfigMap, String> {
+    let (config, migrated) = parse_config(path, read_raw(path)?.as_deref(), legacy)?;
+    if !migrated {
+        return Ok(config);
+    }
+
+    // Redo the migration under the lock in case another process got there first
+    update_at(path, legacy, |config| Ok(config.clone()))
+}
+
+/// Locked read-modify-write of the config at `path`. Writers that do not take
+/// the lock (khaos-tui, a text editor) are detected by re-reading the file
+/// before the write; on a change `update` runs again on the fresh contents.
+fn update_at<T>(
+    path: &Path,
+    legacy: &[PathBuf],
+    mut update: impl FnMut(&mut ConfigMap) -> Result<T, String>,
+) -> Result<T, String> {
+    let _lock = FileLock::acquire(path)?;
+
+    for attempt in 1..=WRITE_ATTEMPTS {
+        let before = read_raw(path)?;
+        let (mut config, _) = parse_config(path, before.as_deref(), legacy)?;
+        let result = update(&mut config)?;
+        config.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+
+        if read_raw(path)? != before {
+            tracing::warn!(
+                "Config {:?} changed while saving (attempt {}), retrying",
+                path,
+                attempt
+            );
+            continue;
+        }
+
+        write_map(path, &config)?;
+        return Ok(result);
+    }
+
+    Err(format!(
+        "Failed to save config: {:?} kept changing during the write",
+        path
+    ))
+}
+
+/// Load the whole config file, migrating older layouts first
+pub fn load_config() -> Result<ConfigMap, String> {
+    load_from(&config_path()?, &legacy_config_paths()?)
+}
+
+/// Apply `update` to the config file under an exclusive lock and write the
+/// result atomically, stamping the schema version. Concurrent updates from
+/// other windows are serialized rather than overwriting each other.
+pub fn update_config<T>(
+    update: impl FnMut(&mut ConfigMap) -> Result<T, String>,
+) -> Result<T, String> {
+    update_at(&config_path()?, &legacy_config_paths()?, update)
+}
+
+fn settings_from(config: &ConfigMap) -> Result<SettingsConfig, String> {
+    let mut settings: SettingsConfig =
+        serde_json::from_value(serde_json::Value::Object(config.clone()))
+            .map_err(|e| format!("Failed to parse config file: {}", e))?;
+
+    // A hand-edited limit of 0 would wipe the history on the next open
+    let limit = settings
+        .recent_projects_limit
+        .clamp(1, MAX_RECENT_PROJECTS_LIMIT);
+    if limit != settings.recent_projects_limit {
+        tracing::warn!(
+            "recent_projects_limit {} is out of range, using {}",
+            settings.recent_projects_limit,
+            limit
+        );
+        settings.recent_projects_limit = limit;
+    }
+    Ok(settings)
+}
 
-    fs::write(&path, content).map_err(|e| format!("Failed to write config file: {}", e))?;
+/// Overwrite the settings fields of `config`, keeping its other keys
+fn merge_settings(config: &mut ConfigMap, settings: &SettingsConfig) -> Result<(), String> {
+    match serde_json::to_value(settings) {
+        Ok(serde_json::Value::Object(fields)) => {
+            config.extend(fields);
+            Ok(())
+        }
+        Ok(_) => Err("Failed to serialize config: settings are not an object".to_string()),
+        Err(e) => Err(format!("Failed to serialize config: {}", e)),
+    }
+}
 
-    tracing::info!("Saved settings to {:?}", path);
+/// Load settings from disk, falling back to defaults for missing fields.
+pub fn load_settings() -> Result<SettingsConfig, String> {
+    settings_from(&load_config()?)
+}
+
+/// Save settings to disk, preserving project history and UI state.
+pub fn save_settings(settings: &SettingsConfig) -> Result<(), String> {
+    update_config(|config| merge_settings(config, settings))?;
+
+    tracing::info!("Saved settings to {:?}", config_path()?);
     Ok(())
 }
 
+/// A string value from khaos-tui's config (step 3 of the resolution order)
+fn tui_config_value(key: &str) -> Option<String> {
+    let path = xdg_config_home().ok()?.join("khaos-tui").join(CONFIG_FILE);
+    read_map(&path)
+        .ok()
+        .flatten()?
+        .get(key)?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn first_non_empty(
+    env_value: Option<String>,
+    configured: Option<String>,
+    tui_value: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    let non_empty = |v: &String| !v.trim().is_empty();
+    env_value
+        .filter(non_empty)
+        .or_else(|| configured.filter(non_empty))
+        .or_else(|| tui_value().filter(non_empty))
+}
+
+/// Resolve a value by steps 1-3 of the resolution order; the caller applies
+/// its own default
+pub fn resolve(env_var: &str, configured: Option<String>, tui_key: &str) -> Option<String> {
+    first_non_empty(std::env::var(env_var).ok(), configured, || {
+        tui_config_value(tui_key)
+    })
+}
+
+/// Get the projects root: KHAOS_PROJECTS_ROOT, `projects_root` in settings,
+/// khaos-tui's `projects_dir`, then $HOME/Projects
+pub fn projects_root(settings: &SettingsConfig) -> Result<String, String> {
+    if let Some(root) = resolve(
+        "KHAOS_PROJECTS_ROOT",
+        settings.projects_root.clone(),
+        "projects_dir",
+    ) {
+        return Ok(root);
+    }
+
+    home_dir()
+        .map(|home| home.join("Projects").to_string_lossy().to_string())
+        .map_err(|_| "Could not determine projects root directory".to_string())
+}
+
+/// Find a Khaos binary ("khaos-tools" or "khaos-wfl"): a configured path that
+/// exists (env var, settings, khaos-tui config), else a PATH lookup
+pub fn find_binary(name: &str) -> Result<String, String> {
+    let settings = load_settings().unwrap_or_default();
+    let (env_var, configured, tui_key) = match name {
+        "khaos-tools" => (
+            "KHAOS_TOOLS_PATH",
+            settings.khaos_tools_path,
+            "khaos_tools_path",
+        ),
+        "khaos-wfl" => ("KHAOS_WFL_PATH", settings.khaos_wfl_path, "khaos_wfl_path"),
+        _ => return Err(format!("Unknown binary: {}", name)),
+    };
+
+    let candidates = [
+        std::env::var(env_var).ok(),
+        configured,
+        tui_config_value(tui_key),
+    ];
+    for path in candidates.into_iter().flatten() {
+        if !path.trim().is_empty() && Path::new(&path).exists() {
+            return Ok(path);
+        }
+    }
+
+    if let Ok(output) = std::process::Command::new("which").arg(name).output() {
+        if output.status.success() {
+            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !path.is_empty() {
+                return Ok(path);
+            }
+        }
+    }
+
+    Err(format!(
+        "{} not found. Ensure it is installed and available on PATH.",
+        name
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn map(value: serde_json::Value) -> ConfigMap {
+        match value {
+            serde_json::Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
     #[test]
     fn test_config_dir_uses_home() {
         let result = config_dir();
         assert!(result.is_ok());
         let dir = result.unwrap();
-        assert!(dir.to_str().unwrap().ends_with(".khaos-ui"));
+        assert!(dir.to_str().unwrap().ends_with("khaos-ui"));
     }
 
     #[test]
@@ -72,6 +496,9 @@ mod tests {
         assert!(result.is_ok());
         let path = result.unwrap();
         assert!(path.to_str().unwrap().ends_with("config.json"));
+        if std::env::var_os("XDG_CONFIG_HOME").is_none() {
+            assert!(path.to_str().unwrap().contains(".config"));
+        }
     }
 
     #[test]
@@ -86,25 +513,170 @@ mod tests {
 
     #[test]
     fn test_save_and_load_roundtrip() {
-        let dir = std::env::temp_dir().join("khaos_config_test");
-        let _ = fs::create_dir_all(&dir);
+        let dir = temp_dir("khaos_config_test");
         let path = dir.join("config.json");
 
         let config = SettingsConfig {
             provider: "openai".to_string(),
             model: Some("gpt-4o-mini".to_string()),
             projects_root: Some("/tmp/projects".to_string()),
+            ..SettingsConfig::default()
         };
 
-        let content = serde_json::to_string_pretty(&config).unwrap();
-        fs::write(&path, &content).unwrap();
+        let mut doc = map(serde_json::json!({ "recent_projects": ["proj_1"] }));
+        merge_settings(&mut doc, &config).unwrap();
+        write_map(&path, &doc).unwrap();
 
-        let loaded: SettingsConfig =
-            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let loaded = load_from(&path, &[]).unwrap();
+        assert_eq!(schema_version(&loaded), SCHEMA_VERSION);
+        assert_eq!(loaded["recent_projects"], serde_json::json!(["proj_1"]));
+
+        let loaded = settings_from(&loaded).unwrap();
         assert_eq!(loaded.provider, "openai");
         assert_eq!(loaded.model, Some("gpt-4o-mini".to_string()));
         assert_eq!(loaded.projects_root, Some("/tmp/projects".to_string()));
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_migrates_legacy_files_once() {
+        let dir = temp_dir("khaos_config_migration");
+        let path = dir.join("new").join("config.json");
+        let discovery = dir.join("discovery.json");
+        let settings = dir.join("settings.json");
+
+        write_map(
+            &discovery,
+            &map(serde_json::json!({
+                "projects_root": "/discovery/root",
+                "recent_projects": ["proj_1"]
+            })),
+        )
+        .unwrap();
+        write_map(
+            &settings,
+            &map(serde_json::json!({
+                "provider": "anthropic",
+                "model": null,
+                "projects_root": "/settings/root"
+            })),
+        )
+        .unwrap();
+
+        let legacy = [discovery.clone(), settings.clone()];
+        let config = load_from(&path, &legacy).unwrap();
+        assert_eq!(schema_version(&config), SCHEMA_VERSION);
+        assert_eq!(config["projects_root"], "/discovery/root");
+        assert_eq!(config["recent_projects"], serde_json::json!(["proj_1"]));
+        assert!(!config.contains_key("model"));
+
+        let loaded = settings_from(&config).unwrap();
+        assert_eq!(loaded.provider, "anthropic");
+        assert_eq!(
+            loaded.recent_projects_limit,
+            SettingsConfig::default().recent_projects_limit
+        );
+
+        // A versioned file is not migrated again
+        fs::write(&settings, r#"{"provider": "groq"}"#).unwrap();
+        let config = load_from(&path, &legacy).unwrap();
+        assert_eq!(config["provider"], "anthropic");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recent_projects_limit_is_clamped() {
+        let loaded =
+            settings_from(&map(serde_json::json!({ "recent_projects_limit": 0 }))).unwrap();
+        assert_eq!(loaded.recent_projects_limit, 1);
+
+        let loaded =
+            settings_from(&map(serde_json::json!({ "recent_projects_limit": 100000 }))).unwrap();
+        assert_eq!(loaded.recent_projects_limit, MAX_RECENT_PROJECTS_LIMIT);
+    }
+
+    #[test]
+    fn test_no_file_written_without_config() {
+        let dir = temp_dir("khaos_config_fresh");
+        let path = dir.join("config.json");
+
+        let config = load_from(&path, &[dir.join("missing.json")]).unwrap();
+        assert_eq!(schema_version(&config), SCHEMA_VERSION);
+        assert!(!path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = temp_dir("khaos_config_concurrent");
+        let path = dir.join("config.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    update_at(&path, &[], |config| {
+                        config.insert(format!("key_{}", i), i.into());
+                        Ok(())
+                    })
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        let config = read_map(&path).unwrap().unwrap();
+        assert_eq!(schema_version(&config), SCHEMA_VERSION);
+        assert!((0..8).all(|i| config.contains_key(&format!("key_{}", i))));
+        // Only the config and its lock file are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_retries_after_external_write() {
+        let dir = temp_dir("khaos_config_conflict");
+        let path = dir.join("config.json");
+        write_map(&path, &map(serde_json::json!({ "schema_version": 1 }))).unwrap();
+
+        let mut calls = 0;
+        update_at(&path, &[], |config| {
+            calls += 1;
+            if calls == 1 {
+                // Another program rewrites the file mid-update
+                fs::write(&path, r#"{"schema_version": 1, "provider": "groq"}"#).unwrap();
+            }
+            config.insert("model".to_string(), "llama-3.1-8b-instant".into());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(calls, 2);
+        let config = read_map(&path).unwrap().unwrap();
+        assert_eq!(config["provider"], "groq");
+        assert_eq!(config["model"], "llama-3.1-8b-instant");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolution_order() {
+        let value = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            first_non_empty(value("/env"), value("/config"), || value("/tui")),
+            value("/env")
+        );
+        assert_eq!(
+            first_non_empty(value(""), value("/config"), || value("/tui")),
+            value("/config")
+        );
+        assert_eq!(first_non_empty(None, None, || value("/tui")), value("/tui"));
+        assert_eq!(first_non_empty(None, value(" "), || None), None);
+    }
 }
@@ -15,62 +15,18 @@ use tokio::process::Command;
 use tokio::sync::{oneshot, Mutex};
 
 use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 // ---------------------------------------------------------------------------
 // CLI binary resolution (shared with import service)
 // ---------------------------------------------------------------------------
 
 fn find_khaos_tools() -> Result<String, String> {
-    if let Ok(path) = load_khaos_tools_from_config() {
-        if Path::new(&path).exists() {
-            return Ok(path);
-        }
-    }
-
-    if let Ok(output) = std::process::Command::new("which")
-        .arg("khaos-tools")
-        .output()
-    {
-        if output.status.success() {
-            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path.is_empty() {
-                return Ok(path);
-            }
-        }
-    }
-
-    Err("khaos-tools not found. Ensure it is installed and available on PATH.".to_string())
-}
-
-fn load_khaos_tools_from_config() -> Result<String, String> {
-    let home = std::env::var("HOME").map_err(|_| "HOME not set".to_string())?;
-    let config_path = format!("{}/.config/khaos-tui/config.json", home);
-
-    let content = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let config: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
-    if let Some(serde_json::Value::String(path)) = config.get("khaos_tools_path") {
-        Ok(path.clone())
-    } else {
-        Err("No khaos_tools_path in config".to_string())
-    }
+    crate::services::config::find_binary("khaos-tools")
 }
 
 fn find_khaos_wfl() -> Result<String, String> {
-    if let Ok(output) = std::process::Command::new("which")
-        .arg("khaos-wfl")
-        .output()
-    {
-        if output.status.success() {
-            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path.is_empty() {
-                return Ok(path);
-            }
-        }
-    }
-
-    Err("khaos-wfl not found. Ensure it is installed and available on PATH.".to_string())
+    crate::services::config::find_binary("khaos-wfl")
 }
 
 // ---------------------------------------------------------------------------
@@ -617,9 +573,21 @@ async fn analyze_via_daemon(
         "verbose": true,
     });
 
-    // Add provider if configured
-    if !settings.provider.is_empty() {
-        params["provider"] = serde_json::json!(settings.provider);
+    // Add provider, effective model and generation parameters if configured
+    if let Some((provider, base_url)) = provider_dispatch(&settings) {
+        params["provider"] = serde_json::json!(provider);
+        if let Some(base_url) = base_url {
+            params["baseUrl"] = serde_json::json!(base_url);
+        }
+    }
+    if let Some(model) = settings.effective_model() {
+        params["model"] = serde_json::json!(model);
+    }
+    if let Some(temperature) = settings.temperature {
+        params["temperature"] = serde_json::json!(temperature);
+    }
+    if let Some(max_tokens) = settings.max_tokens {
+        params["maxTokens"] = serde_json::json!(max_tokens);
     }
 
     // Add entity ID params
@@ -653,6 +621,10 @@ async fn analyze_via_daemon(
                 queued
             );
 
+            if queued {
+                queue_daemon_run(kspd_path, &run_key(item_type, item_id), &settings);
+            }
+
             Ok(AnalysisResult {
                 success: queued,
                 item_type: item_type.to_string(),
@@ -701,40 +673,28 @@ async fn analyze_via_cli(
         "scene" => {
             args.push("analyze-scene".to_string());
             args.push("--save".to_string());
-            if !settings.provider.is_empty() {
-                args.push("--provider".to_string());
-                args.push(settings.provider.clone());
-            }
+            args.extend(generation_args(&settings));
             args.push(kspd_path.to_string());
             args.push(entity_id.to_string());
         }
         "character" => {
             args.push("analyze-character".to_string());
             args.push("--save".to_string());
-            if !settings.provider.is_empty() {
-                args.push("--provider".to_string());
-                args.push(settings.provider.clone());
-            }
+            args.extend(generation_args(&settings));
             args.push(kspd_path.to_string());
             args.push(entity_id.to_string());
         }
         "location" => {
             args.push("analyze-location".to_string());
             args.push("--save".to_string());
-            if !settings.provider.is_empty() {
-                args.push("--provider".to_string());
-                args.push(settings.provider.clone());
-            }
+            args.extend(generation_args(&settings));
             args.push(kspd_path.to_string());
             args.push(entity_id.to_string());
         }
         "analyze-screenplay" => {
             args.push("analyze-screenplay".to_string());
             args.push("--save".to_string());
-            if !settings.provider.is_empty() {
-                args.push("--provider".to_string());
-                args.push(settings.provider.clone());
-            }
+            args.extend(generation_args(&settings));
             args.push(kspd_path.to_string());
         }
         _ => return Err(format!("Unknown entity type: {}", entity_type)),
@@ -746,8 +706,20 @@ async fn analyze_via_cli(
     let item_type = entity_type.to_string();
     let item_id_owned = entity_id.to_string();
 
+    // Stored API key for the provider, visible to the child process only
+    let provider: Vec<_> = settings
+        .find_provider(&settings.provider)
+        .into_iter()
+        .collect();
+    let credentials = crate::services::settings::provider_credentials_env(&provider);
+
     // Spawn and stream output
-    let mut child = Command::new(&cli_path)
+    let mut command = Command::new(&cli_path);
+    crate::services::settings::apply_credentials(&mut command, credentials);
+    if let Some(host) = ollama_host_env(&settings) {
+        command.env("OLLAMA_HOST", host);
+    }
+    let mut child = command
         .args(&args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
@@ -787,6 +759,15 @@ async fn analyze_via_cli(
 
     let success = status.success();
 
+    if success {
+        let key = if entity_type == "analyze-screenplay" {
+            crate::services::analysis_runs::SCREENPLAY_KEY
+        } else {
+            entity_id
+        };
+        record_analysis_run(kspd_path, key, &settings, "cli");
+    }
+
     let _ = app_handle.emit(
         app_events::DAEMON_ANALYSIS_COMPLETED,
         AnalysisCompletedEvent {
@@ -819,6 +800,135 @@ async fn analyze_via_cli(
     })
 }
 
+/// Provider name and base URL to hand to the analysis tools. Custom
+/// OpenAI-compatible providers go through the tools' "openai" provider
+/// pointed at their own base URL.
+fn provider_dispatch(settings: &SettingsConfig) -> Option<(String, Option<String>)> {
+    if settings.provider.is_empty() {
+        return None;
+    }
+    match settings.find_provider(&settings.provider) {
+        Some(provider) if provider.custom => {
+            let name = if provider.openai_compatible {
+                "openai".to_string()
+            } else {
+                provider.id
+            };
+            Some((name, provider.base_url))
+        }
+        _ => Some((settings.provider.clone(), None)),
+    }
+}
+
+/// khaos-tools flags for the provider, effective model and generation parameters
+fn generation_args(settings: &SettingsConfig) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some((provider, base_url)) = provider_dispatch(settings) {
+        args.push("--provider".to_string());
+        args.push(provider);
+        if let Some(base_url) = base_url {
+            args.push("--base-url".to_string());
+            args.push(base_url);
+        }
+    }
+    if let Some(model) = settings.effective_model() {
+        args.push("--model".to_string());
+        args.push(model);
+    }
+    if let Some(temperature) = settings.temperature {
+        args.push("--temperature".to_string());
+        args.push(temperature.to_string());
+    }
+    if let Some(max_tokens) = settings.max_tokens {
+        args.push("--max-tokens".to_string());
+        args.push(max_tokens.to_string());
+    }
+    args
+}
+
+/// OLLAMA_HOST for the analysis tools when Ollama is the selected provider and
+/// a server is set in the settings; otherwise the inherited value applies
+fn ollama_host_env(settings: &SettingsConfig) -> Option<String> {
+    if settings.provider != "ollama" {
+        return None;
+    }
+    settings
+        .ollama_host
+        .as_deref()
+        .filter(|host| !host.trim().is_empty())
+        .map(|host| crate::services::ollama::resolve_host(Some(host)))
+}
+
+/// Record the settings used for an analysis; failures are logged, not returned,
+/// since the analysis itself succeeded. The write waits for the runs file lock,
+/// so it runs on the blocking pool.
+fn record_analysis_run(kspd_path: &str, key: &str, settings: &SettingsConfig, via: &str) {
+    let recorded_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let run = crate::services::analysis_runs::run_from_settings(settings, via, recorded_at);
+    let (kspd_path, key) = (kspd_path.to_string(), key.to_string());
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = crate::services::analysis_runs::record_run(&kspd_path, &key, run) {
+            tracing::warn!("Failed to record analysis run for {}: {}", key, e);
+        }
+    });
+}
+
+/// Settings of daemon analyses that were queued but have not completed yet,
+/// keyed by project path and run key, with the time they were queued
+type PendingRuns = std::collections::HashMap<(String, String), (SettingsConfig, Instant)>;
+
+/// How long a queued daemon run is remembered without a completion event
+const PENDING_RUN_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+static PENDING_DAEMON_RUNS: OnceLock<std::sync::Mutex<PendingRuns>> = OnceLock::new();
+
+fn pending_daemon_runs() -> std::sync::MutexGuard<'static, PendingRuns> {
+    let mut runs = PENDING_DAEMON_RUNS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    expire_daemon_runs(&mut runs, Instant::now());
+    runs
+}
+
+/// Drop runs the daemon never reported back on within the TTL
+fn expire_daemon_runs(runs: &mut PendingRuns, now: Instant) {
+    runs.retain(|_, (_, queued_at)| now.duration_since(*queued_at) < PENDING_RUN_TTL);
+}
+
+/// Key a run is recorded under: the entity ID, or the section for "all"
+fn run_key(item_type: &str, item_id: &str) -> String {
+    if item_id == "all" {
+        format!("{}s", item_type.trim_end_matches('s'))
+    } else {
+        item_id.to_string()
+    }
+}
+
+/// Remember the settings a daemon analysis was queued with
+fn queue_daemon_run(kspd_path: &str, key: &str, settings: &SettingsConfig) {
+    pending_daemon_runs().insert(
+        (kspd_path.to_string(), key.to_string()),
+        (settings.clone(), Instant::now()),
+    );
+}
+
+/// Settings a queued daemon analysis was dispatched with, once the daemon
+/// reports it finished. Returns None for runs this app did not queue.
+fn complete_daemon_run(kspd_path: &str, key: &str) -> Option<SettingsConfig> {
+    pending_daemon_runs()
+        .remove(&(kspd_path.to_string(), key.to_string()))
+        .map(|(settings, _)| settings)
+}
+
+/// Forget every queued run of a project, e.g. once its daemon went away
+fn forget_daemon_runs(kspd_path: &str) {
+    pending_daemon_runs().retain(|(path, _), _| path != kspd_path);
+}
+
 // ---------------------------------------------------------------------------
 // Daemon event bridge — subscribe to wfl events and re-emit to frontend
 // ---------------------------------------------------------------------------
@@ -891,6 +1001,8 @@ async fn run_daemon_event_bridge(
 
                 if let Err(err) = bridge_session(&app, &kspd_path, stream, &mut stop_rx).await {
                     tracing::debug!("Daemon bridge session ended: {}", err);
+                    // Runs queued on a daemon that went away never complete
+                    forget_daemon_runs(&kspd_path);
                 }
             }
         }
@@ -1016,10 +1128,23 @@ fn consume_daemon_envelope(app: &AppHandle, kspd_path: &str, envelope: &serde_js
                     return;
                 }
                 let status = run.status.unwrap_or_else(|| "completed".to_string());
-                let success = !matches!(
-                    status.to_lowercase().as_str(),
-                    "failed" | "error" | "cancelled" | "canceled"
+                let success = run.success.unwrap_or(true)
+                    && run.error.is_none()
+                    && !matches!(
+                        status.to_lowercase().as_str(),
+                        "failed" | "error" | "cancelled" | "canceled"
+                    );
+
+                // Only successful runs produced the saved analysis
+                let key = run_key(
+                    run.target_kind.as_deref().unwrap_or("unknown"),
+                    run.target_id.as_deref().unwrap_or("all"),
                 );
+                if let Some(settings) = complete_daemon_run(kspd_path, &key) {
+                    if success {
+                        record_analysis_run(kspd_path, &key, &settings, "daemon");
+                    }
+                }
 
                 let _ = app.emit(
                     app_events::DAEMON_ANALYSIS_COMPLETED,
@@ -1027,7 +1152,11 @@ fn consume_daemon_envelope(app: &AppHandle, kspd_path: &str, envelope: &serde_js
                         item_type: run.target_kind.unwrap_or_else(|| "unknown".to_string()),
                         item_id: run.target_id.unwrap_or_else(|| "all".to_string()),
                         success,
-                        error: if success { None } else { Some(status) },
+                        error: if success {
+                            None
+                        } else {
+                            Some(run.error.unwrap_or(status))
+                        },
                     },
                 );
             }
@@ -1046,6 +1175,8 @@ struct ParsedWFLRun {
     target_kind: Option<String>,
     target_id: Option<String>,
     project_path: Option<String>,
+    success: Option<bool>,
+    error: Option<String>,
 }
 
 fn parse_wfl_run(data: &serde_json::Map<String, serde_json::Value>) -> Option<ParsedWFLRun> {
@@ -1079,6 +1210,11 @@ fn parse_wfl_run(data: &serde_json::Map<String, serde_json::Value>) -> Option<Pa
         target_kind: target_obj.and_then(|t| as_str(t.get("kind"))),
         target_id: target_obj.and_then(|t| as_str(t.get("id"))),
         project_path,
+        success: base
+            .get("success")
+            .or_else(|| data.get("success"))
+            .and_then(|v| v.as_bool()),
+        error: as_str(base.get("error")).or_else(|| as_str(data.get("error"))),
     })
 }
 
@@ -1119,8 +1255,18 @@ pub async fn start_daemon(kspd_path: &str) -> Result<DaemonStatus, String> {
         socket_path
     );
 
+    // Stored API keys for every provider the daemon may dispatch to, visible
+    // to the daemon process only
+    let settings = crate::services::config::load_settings().unwrap_or_default();
+    let credentials = crate::services::settings::all_provider_credentials_env(&settings);
+
     // Spawn daemon process
-    let _child = Command::new(&wfl_path)
+    let mut command = Command::new(&wfl_path);
+    crate::services::settings::apply_credentials(&mut command, credentials);
+    if let Some(host) = ollama_host_env(&settings) {
+        command.env("OLLAMA_HOST", host);
+    }
+    let _child = command
         .args([
             "daemon",
             "--project",
@@ -1209,6 +1355,133 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_daemon_runs_complete_by_key() {
+        let settings = SettingsConfig::default();
+        let key = run_key("scene", "scene_007");
+        assert_eq!(run_key("scene", "all"), "scenes");
+        assert_eq!(run_key("locations", "all"), "locations");
+
+        queue_daemon_run("/p/daemon-runs.kspd", &key, &settings);
+        assert!(complete_daemon_run("/p/other.kspd", &key).is_none());
+        assert!(complete_daemon_run("/p/daemon-runs.kspd", &key).is_some());
+        assert!(complete_daemon_run("/p/daemon-runs.kspd", &key).is_none());
+
+        queue_daemon_run("/p/daemon-runs.kspd", &key, &settings);
+        forget_daemon_runs("/p/daemon-runs.kspd");
+        assert!(complete_daemon_run("/p/daemon-runs.kspd", &key).is_none());
+    }
+
+    #[test]
+    fn test_daemon_runs_expire() {
+        let now = Instant::now();
+        let mut runs = PendingRuns::new();
+        runs.insert(
+            ("/p/a.kspd".to_string(), "fresh".to_string()),
+            (SettingsConfig::default(), now),
+        );
+        if let Some(stale) = now.checked_sub(PENDING_RUN_TTL) {
+            runs.insert(
+                ("/p/a.kspd".to_string(), "stale".to_string()),
+                (SettingsConfig::default(), stale),
+            );
+        }
+
+        expire_daemon_runs(&mut runs, now);
+        assert_eq!(runs.len(), 1);
+        assert!(runs.contains_key(&("/p/a.kspd".to_string(), "fresh".to_string())));
+    }
+
+    #[test]
+    fn test_parse_wfl_run_reads_failure() {
+        let data = serde_json::json!({
+            "runId": "run-1",
+            "success": false,
+            "error": "Provider unreachable"
+        });
+        let run = parse_wfl_run(data.as_object().unwrap()).unwrap();
+        assert_eq!(run.success, Some(false));
+        assert_eq!(run.error.as_deref(), Some("Provider unreachable"));
+    }
+
+    #[test]
+    fn test_ollama_host_env_only_for_ollama() {
+        let mut settings = SettingsConfig {
+            provider: "ollama".to_string(),
+            ollama_host: Some("gpu-box:11434".to_string()),
+            ..SettingsConfig::default()
+        };
+        assert_eq!(
+            ollama_host_env(&settings).as_deref(),
+            Some("http://gpu-box:11434")
+        );
+
+        settings.ollama_host = Some("  ".to_string());
+        assert_eq!(ollama_host_env(&settings), None);
+
+        settings.provider = "openai".to_string();
+        settings.ollama_host = Some("gpu-box:11434".to_string());
+        assert_eq!(ollama_host_env(&settings), None);
+    }
+
+    #[test]
+    fn test_generation_args_use_effective_model() {
+        let settings = SettingsConfig {
+            provider: "openai".to_string(),
+            temperature: Some(0.3),
+            max_tokens: Some(2048),
+            ..SettingsConfig::default()
+        };
+        assert_eq!(
+            generation_args(&settings),
+            vec![
+                "--provider",
+                "openai",
+                "--model",
+                "gpt-4o",
+                "--temperature",
+                "0.3",
+                "--max-tokens",
+                "2048"
+            ]
+        );
+
+        let settings = SettingsConfig {
+            provider: "ollama".to_string(),
+            model: Some("llama3:8b".to_string()),
+            ..SettingsConfig::default()
+        };
+        assert_eq!(
+            generation_args(&settings),
+            vec!["--provider", "ollama", "--model", "llama3:8b"]
+        );
+
+        let settings = SettingsConfig {
+            provider: "lmstudio".to_string(),
+            custom_providers: vec![crate::types::CustomProvider {
+                id: "lmstudio".to_string(),
+                title: "LM Studio".to_string(),
+                base_url: "http://localhost:1234/v1".to_string(),
+                api_key_env: None,
+                secret_ref: None,
+                default_model: "qwen2.5-7b-instruct".to_string(),
+                openai_compatible: true,
+            }],
+            ..SettingsConfig::default()
+        };
+        assert_eq!(
+            generation_args(&settings),
+            vec![
+                "--provider",
+                "openai",
+                "--base-url",
+                "http://localhost:1234/v1",
+                "--model",
+                "qwen2.5-7b-instruct"
+            ]
+        );
+    }
+
     #[test]
     fn test_daemon_socket_path() {
         let path = daemon_socket_path("/Users/k/projects/test.kspd");
@@ -1,67 +1,263 @@
 // Project Discovery Service
 // Scans configured roots for KSPD projects following khaos-tui patterns
 
-use crate::types::{Project, ProjectManifest};
+use crate::services::project_index::{self, Fingerprint, ProjectIndex};
+use crate::services::{config, dashboard};
+use crate::types::{
+    AnalysisCoverage, DiscoveryReport, Project, ProjectManifest, ProjectRoot, RecentProject,
+    RootError,
+};
+use glob::Pattern;
+use std::cmp::Reverse;
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
 
-/// Discover all KSPD projects in a directory
-pub fn discover_projects(root: &str) -> Result<Vec<Project>, String> {
-    let root_path = Path::new(root);
+/// Discover projects across several roots.
+/// A root that cannot be scanned is reported in `errors` without aborting the others;
+/// projects reachable from overlapping roots are listed once. Unchanged projects
+/// are served from the persistent project index.
+pub fn discover_in_roots(roots: &[ProjectRoot]) -> DiscoveryReport {
+    project_index::with_index(|index| {
+        let mut projects: Vec<Project> = Vec::new();
+        let mut errors = Vec::new();
+        let mut seen = HashSet::new();
+
+        for root in roots {
+            match discover_projects(root, index) {
+                Ok(found) => {
+                    for project in found {
+                        if seen.insert(project.path.clone()) {
+                            projects.push(project);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to scan project root {}: {}", root.path, e);
+                    errors.push(RootError {
+                        root: root.path.clone(),
+                        error: e,
+                    });
+                }
+            }
+        }
+
+        // Only prune when every root was scanned, so a temporarily
+        // unavailable root keeps its cached entries
+        if errors.is_empty() {
+            index.retain_paths(&seen);
+        }
+
+        projects.sort_by_key(|p| Reverse(p.modified));
+        DiscoveryReport { projects, errors }
+    })
+}
+
+/// Recursively discover all KSPD projects below one root.
+/// Descent stops at any directory identified as a KSPD, at `max_depth`,
+/// and at directories matching an exclude pattern. `index` is consulted and
+/// updated for each project found.
+pub fn discover_projects(
+    root: &ProjectRoot,
+    index: &mut ProjectIndex,
+) -> Result<Vec<Project>, String> {
+    let root_path = Path::new(&root.path);
 
     // Verify root exists and is a directory
     if !root_path.is_dir() {
-        return Err(format!("Projects directory not found: {}", root));
+        return Err(format!("Projects directory not found: {}", root.path));
     }
 
-    let mut projects = Vec::new();
-    let mut errors = Vec::new();
+    let include = compile_patterns(&root.include)?;
+    let exclude = compile_patterns(&root.exclude)?;
+
+    let mut scan = RootScan {
+        root_path,
+        max_depth: root.max_depth.max(1),
+        include,
+        exclude,
+        index,
+        projects: Vec::new(),
+        errors: Vec::new(),
+    };
 
-    // Read all entries in root directory
-    match fs::read_dir(root_path) {
-        Ok(entries) => {
-            for entry in entries {
-                match entry {
-                    Ok(entry) => {
-                        let path = entry.path();
+    let entries =
+        fs::read_dir(root_path).map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    scan.visit(entries, 1);
 
-                        // Skip symlinks
-                        if let Ok(metadata) = fs::symlink_metadata(&path) {
-                            if metadata.is_symlink() {
-                                continue;
-                            }
-                        }
+    // Log non-fatal errors
+    for error in &scan.errors {
+        tracing::warn!("{}", error);
+    }
 
-                        // Check if this is a KSPD project
-                        if is_kspd(&path) {
-                            match read_project_metadata(&path) {
-                                Ok(project) => projects.push(project),
-                                Err(e) => errors.push(format!(
-                                    "Failed to read project {}: {}",
-                                    path.display(),
-                                    e
-                                )),
-                            }
-                        }
+    let mut projects = scan.projects;
+
+    // Sort by modification time (newest first)
+    projects.sort_by(|a, b| b.modified.cmp(&a.modified));
+
+    tracing::info!("Discovered {} projects in {}", projects.len(), root.path);
+    Ok(projects)
+}
+
+/// Compile glob patterns, failing on the first invalid one
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>, String> {
+    patterns
+        .iter()
+        .map(|p| Pattern::new(p).map_err(|e| format!("Invalid pattern \"{}\": {}", p, e)))
+        .collect()
+}
+
+/// State for a recursive walk of a single root
+struct RootScan<'a> {
+    root_path: &'a Path,
+    max_depth: usize,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    index: &'a mut ProjectIndex,
+    projects: Vec<Project>,
+    errors: Vec<String>,
+}
+
+impl RootScan<'_> {
+    fn walk(&mut self, dir: &Path, depth: usize) {
+        match fs::read_dir(dir) {
+            Ok(entries) => self.visit(entries, depth),
+            Err(e) => {
+                self.errors
+                    .push(format!("Failed to read directory {}: {}", dir.display(), e))
+            }
+        }
+    }
+
+    fn visit(&mut self, entries: fs::ReadDir, depth: usize) {
+        for entry in entries {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    self.errors.push(format!("Failed to read directory entry: {}", e));
+                    continue;
+                }
+            };
+
+            // Skip symlinks and plain files
+            match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.is_dir() => {}
+                _ => continue,
+            }
+
+            if matches_any(&self.exclude, self.root_path, &path) {
+                continue;
+            }
+
+            // Check if this is a KSPD project; never descend into one
+            if is_kspd(&path) {
+                if self.include.is_empty() || matches_any(&self.include, self.root_path, &path) {
+                    match self.read_project(&path) {
+                        Ok(project) => self.projects.push(project),
+                        Err(e) => self.errors.push(format!(
+                            "Failed to read project {}: {}",
+                            path.display(),
+                            e
+                        )),
                     }
-                    Err(e) => errors.push(format!("Failed to read directory entry: {}", e)),
                 }
+                continue;
+            }
+
+            if depth < self.max_depth {
+                self.walk(&path, depth + 1);
             }
         }
-        Err(e) => return Err(format!("Failed to read projects directory: {}", e)),
     }
 
-    // Log non-fatal errors
-    for error in errors {
-        tracing::warn!("{}", error);
+    fn read_project(&mut self, path: &Path) -> Result<Project, String> {
+        read_indexed(self.index, path)
     }
+}
 
-    // Sort by modification time (newest first)
-    projects.sort_by(|a, b| b.modified.cmp(&a.modified));
+/// Read project metadata, reusing the indexed record when its files are unchanged
+fn read_indexed(index: &mut ProjectIndex, path: &Path) -> Result<Project, String> {
+    let path_str = path.to_str().ok_or("Invalid path encoding")?.to_string();
+    let fingerprint = Fingerprint::of(path);
 
-    tracing::info!("Discovered {} projects in {}", projects.len(), root);
-    Ok(projects)
+    if let Some(project) = index.get(&path_str, &fingerprint) {
+        return Ok(project);
+    }
+
+    let project = read_project_metadata(path)?;
+    index.insert(path_str, fingerprint, project.clone());
+    Ok(project)
+}
+
+/// Read the project at `path` if a scan of `roots` would list it, without
+/// scanning the roots. Returns None when the directory is not (or no longer)
+/// a discoverable project.
+pub fn discover_project_at(roots: &[ProjectRoot], path: &Path) -> Option<Project> {
+    if !roots.iter().any(|root| reachable_from(root, path)) {
+        return None;
+    }
+    project_index::with_index(|index| read_indexed(index, path))
+        .map_err(|e| tracing::warn!("Failed to read project {}: {}", path.display(), e))
+        .ok()
+}
+
+/// Whether a scan of `root` finds a project at `path`: every directory on the
+/// way is entered by the walk (within depth, not excluded, not a symlink, not
+/// itself a project) and the project matches the include patterns
+fn reachable_from(root: &ProjectRoot, path: &Path) -> bool {
+    let root_path = Path::new(&root.path);
+    let Ok(relative) = path.strip_prefix(root_path) else {
+        return false;
+    };
+    let depth = relative.components().count();
+    if depth == 0 || depth > root.max_depth.max(1) || !is_kspd(path) {
+        return false;
+    }
+    let (Ok(include), Ok(exclude)) = (
+        compile_patterns(&root.include),
+        compile_patterns(&root.exclude),
+    ) else {
+        return false;
+    };
+
+    let mut dir = root_path.to_path_buf();
+    for component in relative.components() {
+        dir.push(component);
+        match fs::symlink_metadata(&dir) {
+            Ok(metadata) if metadata.is_dir() => {}
+            _ => return false,
+        }
+        if matches_any(&exclude, root_path, &dir) || (dir != path && is_kspd(&dir)) {
+            return false;
+        }
+    }
+
+    include.is_empty() || matches_any(&include, root_path, path)
+}
+
+/// Match a directory against glob patterns by its path relative to the root
+/// (e.g. `ClientA/**`) or by its name alone (e.g. `archive`)
+fn matches_any(patterns: &[Pattern], root: &Path, path: &Path) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    patterns
+        .iter()
+        .any(|p| p.matches(&relative) || p.matches(&name))
 }
 
 /// Check if a path is a valid KSPD project
@@ -81,7 +277,7 @@ pub fn is_kspd(path: &Path) -> bool {
 }
 
 /// Read project metadata from directory
-fn read_project_metadata(path: &Path) -> Result<Project, String> {
+pub fn read_project_metadata(path: &Path) -> Result<Project, String> {
     let path_str = path
         .to_str()
         .ok_or("Invalid path encoding")?
@@ -94,17 +290,15 @@ fn read_project_metadata(path: &Path) -> Result<Project, String> {
         .ok_or("Invalid directory name")?
         .to_string();
 
-    // Try to read title from manifest.json
+    // Read title and author from manifest.json if present
     let manifest_path = path.join("manifest.json");
-    if let Ok(manifest_data) = fs::read_to_string(&manifest_path) {
-        if let Ok(manifest) =
-            serde_json::from_str::<ProjectManifest>(&manifest_data)
-        {
-            if let Some(manifest_title) = manifest.title {
-                if !manifest_title.is_empty() {
-                    title = manifest_title;
-                }
-            }
+    let manifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<ProjectManifest>(&data).ok());
+
+    if let Some(manifest_title) = manifest.as_ref().and_then(|m| m.title.clone()) {
+        if !manifest_title.is_empty() {
+            title = manifest_title;
         }
     }
 
@@ -113,17 +307,9 @@ fn read_project_metadata(path: &Path) -> Result<Project, String> {
         title = title[..title.len() - 5].to_string();
     }
 
-    // Get author from manifest if available
-    let author = if let Ok(manifest_data) = fs::read_to_string(&manifest_path) {
-        if let Ok(manifest) =
-            serde_json::from_str::<ProjectManifest>(&manifest_data)
-        {
-            manifest.author
-        } else {
-            None
-        }
-    } else {
-        None
+    let (author, description) = match manifest {
+        Some(m) => (m.author, m.description),
+        None => (None, None),
     };
 
     // Get modification time
@@ -135,8 +321,23 @@ fn read_project_metadata(path: &Path) -> Result<Project, String> {
         .map_err(|e| e.to_string())?
         .as_secs() as i64;
 
-    // Count scenes from metadata/scenes.json
+    // Count entities from metadata/*.json
     let scene_count = count_entities(path, "scenes.json", "scenes");
+    let character_count = count_entities(path, "characters.json", "characters");
+    let location_count = count_entities(path, "locations.json", "locations");
+
+    let ingested = path
+        .join("metadata")
+        .join("artifacts")
+        .join("story-storage.db")
+        .is_file();
+
+    let index = dashboard::scan_analysis_index(&path_str);
+    let analysis_coverage = AnalysisCoverage {
+        scenes: coverage_percent(index.scenes.len(), scene_count),
+        characters: coverage_percent(index.characters.len(), character_count),
+        locations: coverage_percent(index.locations.len(), location_count),
+    };
 
     Ok(Project {
         id: Project::id_from_path(&path_str),
@@ -145,9 +346,41 @@ fn read_project_metadata(path: &Path) -> Result<Project, String> {
         path: path_str,
         scene_count,
         modified,
+        description,
+        character_count,
+        location_count,
+        ingested,
+        analysis_coverage,
+        size_bytes: dir_size(path),
+        // User organization state is applied by project_state
+        ..Default::default()
     })
 }
 
+/// Percentage of `total` entities that have saved analysis, capped at 100
+fn coverage_percent(analyzed: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (analyzed as f64 / total as f64 * 100.0).min(100.0)
+}
+
+/// Total size of regular files below a directory, not following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(ft) if ft.is_dir() => dir_size(&entry.path()),
+            Ok(ft) if ft.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
 /// Count entities in a JSON array file
 fn count_entities(project_path: &Path, filename: &str, array_key: &str) -> usize {
     let file_path = project_path.join("metadata").join(filename);
@@ -175,162 +408,174 @@ fn count_entities(project_path: &Path, filename: &str, array_key: &str) -> usize
     }
 }
 
-/// Get the projects root directory
-/// Resolution order:
-/// 1. KHAOS_PROJECTS_ROOT environment variable
-/// 2. ~/.config/khaos-tui/config.json projects_dir field (shared with khaos-tui)
-/// 3. ~/.config/khaos-ui/config.json projects_root field (UI-specific override)
-/// 4. Default: $HOME/Projects
-pub fn get_projects_root() -> Result<String, String> {
-    // Check environment variable first
-    if let Ok(root) = std::env::var("KHAOS_PROJECTS_ROOT") {
-        if !root.is_empty() {
-            return Ok(root);
-        }
-    }
-
-    // Check TUI config file (shared config with khaos-tui)
-    if let Ok(root) = load_projects_dir_from_tui_config() {
-        return Ok(root);
-    }
+/// Get the roots to scan for projects.
+/// Uses `project_roots` from settings when configured, otherwise the single
+/// directory resolved by `get_projects_root`.
+pub fn get_project_roots() -> Result<Vec<ProjectRoot>, String> {
+    let settings = config::load_settings().unwrap_or_default();
+    let roots: Vec<ProjectRoot> = settings
+        .project_roots
+        .into_iter()
+        .filter(|root| !root.path.trim().is_empty())
+        .collect();
 
-    // Check UI config file (UI-specific override)
-    if let Ok(root) = load_projects_root_from_ui_config() {
-        return Ok(root);
+    if !roots.is_empty() {
+        return Ok(roots);
     }
 
-    // Fall back to $HOME/Projects
-    match std::env::var("HOME") {
-        Ok(home) => {
-            let default_root = format!("{}/Projects", home);
-            Ok(default_root)
-        }
-        Err(_) => Err("Could not determine projects root directory".to_string()),
-    }
+    Ok(vec![ProjectRoot::new(get_projects_root()?)])
 }
 
-/// Load projects_dir from TUI config file (~/.config/khaos-tui/config.json)
-/// This is the shared config with khaos-tui
-fn load_projects_dir_from_tui_config() -> Result<String, String> {
-    let config_path = get_tui_config_path()?;
+/// The root new projects are created or imported into: `root` when it is one
+/// of the scanned roots, else the first scanned root, so discovery and the
+/// watcher see the new project
+pub fn target_root(root: Option<&str>) -> Result<String, String> {
+    pick_root(get_project_roots()?, root)
+}
 
-    match fs::read_to_string(&config_path) {
-        Ok(content) => {
-            if let Ok(config) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(
-                &content,
-            ) {
-                if let Some(serde_json::Value::String(root)) = config.get("projects_dir") {
-                    return Ok(root.clone());
-                }
-            }
-            Err("No projects_dir in TUI config".to_string())
-        }
-        Err(_) => Err("TUI config file not found".to_string()),
-    }
+fn pick_root(roots: Vec<ProjectRoot>, root: Option<&str>) -> Result<String, String> {
+    let mut roots = roots.into_iter();
+    let picked = match root {
+        Some(root) => roots.find(|r| Path::new(&r.path) == Path::new(root)),
+        None => roots.next(),
+    };
+    picked.map(|r| r.path).ok_or_else(|| match root {
+        Some(root) => format!("Not a configured project root: {}", root),
+        None => "No project roots configured".to_string(),
+    })
 }
 
-/// Load projects_root from UI config file (~/.config/khaos-ui/config.json)
-/// This is an optional UI-specific override
-fn load_projects_root_from_ui_config() -> Result<String, String> {
-    let config_path = get_ui_config_path()?;
+/// Get the projects root directory, resolved in the order documented in the
+/// config service (KHAOS_PROJECTS_ROOT, settings `projects_root`, khaos-tui
+/// `projects_dir`, then $HOME/Projects)
+pub fn get_projects_root() -> Result<String, String> {
+    config::projects_root(&config::load_settings().unwrap_or_default())
+}
 
-    match fs::read_to_string(&config_path) {
-        Ok(content) => {
-            if let Ok(config) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(
-                &content,
-            ) {
-                if let Some(serde_json::Value::String(root)) = config.get("projects_root") {
-                    return Ok(root.clone());
-                }
-            }
-            Err("No projects_root in UI config".to_string())
-        }
-        Err(_) => Err("UI config file not found".to_string()),
-    }
+/// Apply `update` to the recent projects history and save it, keeping the
+/// `limit` most recent, as one locked update of the config so concurrent
+/// windows don't drop each other's entries. `update` runs again if the file
+/// changed underneath it.
+pub fn update_recent_projects<T>(
+    limit: usize,
+    mut update: impl FnMut(&mut Vec<RecentProject>) -> T,
+) -> Result<T, String> {
+    config::update_config(|config| update_recent(config, limit, &mut update))
 }
 
-/// Get TUI config file path (~/.config/khaos-tui/config.json)
-fn get_tui_config_path() -> Result<String, String> {
-    match std::env::var("HOME") {
-        Ok(home) => {
-            let path = format!("{}/.config/khaos-tui/config.json", home);
-            Ok(path)
-        }
-        Err(_) => Err("Could not determine HOME directory".to_string()),
-    }
+fn update_recent<T>(
+    config: &mut config::ConfigMap,
+    limit: usize,
+    update: impl FnOnce(&mut Vec<RecentProject>) -> T,
+) -> Result<T, String> {
+    let mut history = recent_from_config(config);
+    let result = update(&mut history);
+    history.truncate(limit);
+
+    let recent = serde_json::to_value(&history)
+        .map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+    config.insert("recent_projects".to_string(), recent);
+    Ok(result)
 }
 
-/// Get UI config file path (~/.config/khaos-ui/config.json)
-fn get_ui_config_path() -> Result<String, String> {
-    match std::env::var("HOME") {
-        Ok(home) => {
-            let path = format!("{}/.config/khaos-ui/config.json", home);
-            Ok(path)
-        }
-        Err(_) => Err("Could not determine HOME directory".to_string()),
-    }
+/// Load the recent projects history from the config, most recent first.
+/// Entries saved as bare IDs by older versions load without path or timestamp.
+pub fn load_recent_projects() -> Result<Vec<RecentProject>, String> {
+    Ok(recent_from_config(
+        &config::load_config().unwrap_or_default(),
+    ))
 }
 
-/// Save recent projects list to UI config
-pub fn save_recent_projects(project_ids: &[String]) -> Result<(), String> {
-    let config_path = get_ui_config_path()?;
-    let config_dir = Path::new(&config_path)
-        .parent()
-        .ok_or("Invalid config path")?;
-
-    // Create config directory if it doesn't exist
-    fs::create_dir_all(config_dir).map_err(|e| e.to_string())?;
-
-    // Load existing config
-    let mut config: serde_json::Map<String, serde_json::Value> =
-        if let Ok(content) = fs::read_to_string(&config_path) {
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            serde_json::Map::new()
-        };
-
-    // Update recent projects (limit to last 5)
-    let recent: Vec<serde_json::Value> = project_ids
+fn recent_from_config(config: &config::ConfigMap) -> Vec<RecentProject> {
+    let Some(serde_json::Value::Array(recent)) = config.get("recent_projects") else {
+        return vec![];
+    };
+
+    recent
         .iter()
-        .take(5)
-        .map(|id| serde_json::Value::String(id.clone()))
-        .collect();
+        .filter_map(|v| match v {
+            serde_json::Value::String(id) => Some(RecentProject {
+                id: id.clone(),
+                path: String::new(),
+                opened_at: 0,
+                section: None,
+            }),
+            other => serde_json::from_value(other.clone()).ok(),
+        })
+        .collect()
+}
 
-    config.insert(
-        "recent_projects".to_string(),
-        serde_json::Value::Array(recent),
+/// Move a project to the front of the history with the given open time,
+/// keeping the dashboard section it was last shown in
+pub fn record_recent_project(
+    history: &mut Vec<RecentProject>,
+    project: &Project,
+    opened_at: i64,
+    limit: usize,
+) {
+    let section = history
+        .iter()
+        .find(|entry| entry.id == project.id)
+        .and_then(|entry| entry.section.clone());
+    history.retain(|entry| entry.id != project.id);
+    history.insert(
+        0,
+        RecentProject {
+            id: project.id.clone(),
+            path: project.path.clone(),
+            opened_at,
+            section,
+        },
     );
-
-    // Write config back
-    let json = serde_json::to_string_pretty(&config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())?;
-
-    Ok(())
+    history.truncate(limit);
 }
 
-/// Load recent projects from UI config
-pub fn load_recent_projects() -> Result<Vec<String>, String> {
-    let config_path = get_ui_config_path()?;
+/// Remember the dashboard section shown for the project at `project_path`.
+/// Returns false when the project is not in the history.
+pub fn record_recent_section(
+    history: &mut [RecentProject],
+    project_path: &str,
+    section: &str,
+) -> bool {
+    match history.iter_mut().find(|entry| entry.path == project_path) {
+        Some(entry) => {
+            entry.section = Some(section.to_string());
+            true
+        }
+        None => false,
+    }
+}
 
-    match fs::read_to_string(&config_path) {
-        Ok(content) => {
-            if let Ok(config) =
-                serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(
-                    &content,
-                )
-            {
-                if let Some(serde_json::Value::Array(recent)) = config.get("recent_projects") {
-                    let ids: Vec<String> = recent
-                        .iter()
-                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                        .collect();
-                    return Ok(ids);
-                }
-            }
-            Ok(vec![])
+/// Resolve history entries against discovered projects, most recent first,
+/// with `last_opened` set. Entries that match no project and whose path no
+/// longer exists are dropped from `history`; entries under a root that is
+/// merely unavailable are kept but not returned.
+pub fn resolve_recent_projects(
+    history: &mut Vec<RecentProject>,
+    projects: &[Project],
+) -> Vec<Project> {
+    let mut resolved = Vec::new();
+
+    history.retain(|entry| match projects.iter().find(|p| p.id == entry.id) {
+        Some(project) => {
+            let mut project = project.clone();
+            project.last_opened = Some(entry.opened_at);
+            project.last_section = entry.section.clone();
+            resolved.push(project);
+            true
         }
-        Err(_) => Ok(vec![]),
+        None => !entry.path.is_empty() && Path::new(&entry.path).exists(),
+    });
+
+    resolved
+}
+
+/// Set `last_opened` and `last_section` on projects that appear in the history
+pub fn apply_last_opened(history: &[RecentProject], projects: &mut [Project]) {
+    for project in projects {
+        let entry = history.iter().find(|entry| entry.id == project.id);
+        project.last_opened = entry.map(|entry| entry.opened_at);
+        project.last_section = entry.and_then(|entry| entry.section.clone());
     }
 }
 
@@ -411,7 +656,8 @@ mod tests {
         let test_root = setup_test_projects();
         let root_str = test_root.to_str().unwrap();
 
-        let projects = discover_projects(root_str).unwrap();
+        let projects =
+            discover_projects(&ProjectRoot::new(root_str), &mut ProjectIndex::default()).unwrap();
 
         // Should find 2 projects (screenplay-1.kspd and draft-project)
         assert_eq!(projects.len(), 2);
@@ -434,6 +680,77 @@ mod tests {
         assert_eq!(project.scene_count, 3);
     }
 
+    #[test]
+    fn test_read_project_metadata_tolerates_mistyped_manifest() {
+        let proj_path = std::env::temp_dir().join("khaos_discovery_mistyped/odd.kspd");
+        let _ = fs::remove_dir_all(proj_path.parent().unwrap());
+        fs::create_dir_all(&proj_path).unwrap();
+        fs::write(
+            proj_path.join("manifest.json"),
+            r#"{"title": "Odd Draft", "author": "Jane", "draft_number": "2a", "genre": ["noir"]}"#,
+        )
+        .unwrap();
+
+        let project = read_project_metadata(&proj_path).unwrap();
+        assert_eq!(project.title, "Odd Draft");
+        assert_eq!(project.author, Some("Jane".to_string()));
+
+        let _ = fs::remove_dir_all(proj_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_read_project_metadata_counts_and_coverage() {
+        let test_root = setup_test_projects();
+        let proj_path = test_root.join("screenplay-1.kspd");
+        fs::write(
+            proj_path.join("manifest.json"),
+            r#"{"title": "My Screenplay", "description": "A heist"}"#,
+        )
+        .unwrap();
+        fs::write(
+            proj_path.join("metadata/characters.json"),
+            r#"{"characters": [{"id": "c1"}, {"id": "c2"}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            proj_path.join("metadata/locations.json"),
+            r#"[{"id": "l1"}]"#,
+        )
+        .unwrap();
+        fs::create_dir_all(proj_path.join("metadata/artifacts")).unwrap();
+        fs::write(proj_path.join("metadata/artifacts/story-storage.db"), "db").unwrap();
+        let analysis_dir = proj_path.join("metadata/analysis");
+        fs::create_dir_all(&analysis_dir).unwrap();
+        fs::write(analysis_dir.join("scn_001.json"), "{}").unwrap();
+        fs::write(analysis_dir.join("chr_001.json"), "{}").unwrap();
+        fs::write(analysis_dir.join("chr_002.json"), "{}").unwrap();
+
+        let project = read_project_metadata(&proj_path).unwrap();
+
+        assert_eq!(project.description, Some("A heist".to_string()));
+        assert_eq!(project.character_count, 2);
+        assert_eq!(project.location_count, 1);
+        assert!(project.ingested);
+        assert!((project.analysis_coverage.scenes - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(project.analysis_coverage.characters, 100.0);
+        assert_eq!(project.analysis_coverage.locations, 0.0);
+        assert!(project.size_bytes > 0);
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_read_project_metadata_not_ingested() {
+        let test_root = setup_test_projects();
+        let project = read_project_metadata(&test_root.join("draft-project")).unwrap();
+
+        assert!(!project.ingested);
+        assert_eq!(project.character_count, 0);
+        assert_eq!(project.analysis_coverage.scenes, 0.0);
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
     #[test]
     fn test_read_project_metadata_scene_count_object_format() {
         let test_root = setup_test_projects();
@@ -468,7 +785,8 @@ mod tests {
         let test_root = setup_test_projects();
         let root_str = test_root.to_str().unwrap();
 
-        let projects = discover_projects(root_str).unwrap();
+        let projects =
+            discover_projects(&ProjectRoot::new(root_str), &mut ProjectIndex::default()).unwrap();
 
         // Verify they're sorted by modification time (should be newest first)
         for i in 0..projects.len() - 1 {
@@ -476,78 +794,122 @@ mod tests {
         }
     }
 
+    fn recent(id: &str, path: &str, opened_at: i64) -> RecentProject {
+        RecentProject {
+            id: id.to_string(),
+            path: path.to_string(),
+            opened_at,
+            section: None,
+        }
+    }
+
+    fn project_at(path: &str) -> Project {
+        Project {
+            id: Project::id_from_path(path),
+            title: "Test".to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
     #[test]
-    fn test_save_recent_projects_respects_limit() {
-        // Test that save_recent_projects limits output to 5 even when given more
-        // This test verifies the implementation logic without relying on persistent state
-        let project_ids = vec![
-            "proj-1".to_string(),
-            "proj-2".to_string(),
-            "proj-3".to_string(),
-            "proj-4".to_string(),
-            "proj-5".to_string(),
-            "proj-6".to_string(),
-            "proj-7".to_string(),
-        ];
+    fn test_record_recent_project_moves_to_front_and_limits() {
+        let mut history: Vec<RecentProject> = (1..=7)
+            .map(|i| recent(&format!("proj-{}", i), "", i))
+            .collect();
+        let mut project = project_at("/p/proj-3.kspd");
+        project.id = "proj-3".to_string();
 
-        // The implementation should limit to first 5
-        let limited: Vec<_> = project_ids.iter().take(5).collect();
-        assert_eq!(limited.len(), 5, "Implementation should take only first 5");
-        assert_eq!(*limited[0], "proj-1");
-        assert_eq!(*limited[4], "proj-5");
+        record_recent_project(&mut history, &project, 100, 5);
+
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0], recent("proj-3", "/p/proj-3.kspd", 100));
+        assert_eq!(history[1].id, "proj-1");
+        assert_eq!(history.iter().filter(|e| e.id == "proj-3").count(), 1);
     }
 
     #[test]
-    fn test_save_recent_projects_succeeds() {
-        // Test that save_recent_projects completes without error
-        let project_ids = vec![
-            "test-proj-1".to_string(),
-            "test-proj-2".to_string(),
+    fn test_resolve_recent_projects_prunes_missing() {
+        let existing_dir = std::env::temp_dir();
+        let existing = existing_dir.to_string_lossy().to_string();
+        let found = project_at("/p/found.kspd");
+
+        let mut history = vec![
+            recent(&found.id, &found.path, 30),
+            recent("proj_gone", "/definitely/not/here.kspd", 20),
+            recent("proj_offline", &existing, 10),
+            recent("proj_legacy", "", 0),
         ];
 
-        let result = save_recent_projects(&project_ids);
-        assert!(result.is_ok(), "save_recent_projects should succeed with valid input");
+        let resolved = resolve_recent_projects(&mut history, std::slice::from_ref(&found));
+
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].id, found.id);
+        assert_eq!(resolved[0].last_opened, Some(30));
+        // The unavailable-but-present project is kept for later
+        let kept: Vec<&str> = history.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(kept, vec![found.id.as_str(), "proj_offline"]);
     }
 
     #[test]
-    fn test_load_recent_projects_never_errors() {
-        // Test that load_recent_projects always returns Ok
-        let result = load_recent_projects();
-        assert!(result.is_ok(), "load_recent_projects should always return Ok, never Err");
+    fn test_apply_last_opened() {
+        let mut projects = vec![project_at("/p/a.kspd"), project_at("/p/b.kspd")];
+        let history = vec![recent(&projects[1].id, "/p/b.kspd", 42)];
+
+        apply_last_opened(&history, &mut projects);
+
+        assert_eq!(projects[0].last_opened, None);
+        assert_eq!(projects[1].last_opened, Some(42));
     }
 
     #[test]
-    fn test_get_ui_config_path_resolves() {
-        let result = get_ui_config_path();
-        assert!(result.is_ok(), "get_ui_config_path should resolve successfully");
-
-        let config_path = result.unwrap();
-        assert!(!config_path.is_empty(), "Config path should not be empty");
-        assert!(config_path.contains(".config"), "Config path should contain .config");
+    fn test_recent_section_survives_reopen() {
+        let project = project_at("/p/a.kspd");
+        let mut history = vec![recent(&project.id, &project.path, 1)];
+
+        let recorded = record_recent_section(&mut history, "/p/a.kspd", "locations");
+        assert!(recorded);
+        let recorded = record_recent_section(&mut history, "/p/b.kspd", "scenes");
+        assert!(!recorded);
+        record_recent_project(&mut history, &project, 2, 5);
+
+        assert_eq!(history[0].opened_at, 2);
+        assert_eq!(history[0].section.as_deref(), Some("locations"));
+        let resolved = resolve_recent_projects(&mut history, std::slice::from_ref(&project));
+        assert_eq!(resolved[0].last_section.as_deref(), Some("locations"));
     }
 
     #[test]
-    fn test_save_and_load_recent_projects() {
-        let project_ids = vec!["proj_1".to_string(), "proj_2".to_string()];
-        assert!(save_recent_projects(&project_ids).is_ok());
+    fn test_update_recent_round_trips_through_config() {
+        let mut config = config::ConfigMap::new();
+        let entries = vec![
+            recent("proj_1", "/p/1.kspd", 2),
+            recent("proj_2", "/p/2.kspd", 1),
+        ];
 
-        let loaded = load_recent_projects().unwrap();
-        // The loaded list should contain our saved projects
-        assert!(loaded.len() >= 2, "Expected at least 2 projects, got {}", loaded.len());
-        // Check that our saved projects are in the loaded list
-        assert!(loaded.contains(&"proj_1".to_string()));
-        assert!(loaded.contains(&"proj_2".to_string()));
+        update_recent(&mut config, 10, |history| *history = entries.clone()).unwrap();
+        assert_eq!(recent_from_config(&config), entries);
     }
 
     #[test]
-    fn test_recent_projects_limited_to_5() {
-        let mut project_ids: Vec<String> = (1..=10)
-            .map(|i| format!("proj_{}", i))
+    fn test_update_recent_limits_history() {
+        let mut config = config::ConfigMap::new();
+        let entries: Vec<RecentProject> = (1..=10)
+            .map(|i| recent(&format!("proj_{}", i), "", i))
             .collect();
-        assert!(save_recent_projects(&project_ids).is_ok());
 
-        let loaded = load_recent_projects().unwrap();
-        assert!(loaded.len() <= 5);
+        update_recent(&mut config, 5, |history| *history = entries.clone()).unwrap();
+        assert_eq!(recent_from_config(&config), entries[..5]);
+    }
+
+    #[test]
+    fn test_recent_from_config_reads_bare_ids() {
+        let config = serde_json::json!({ "recent_projects": ["proj_1", 42] });
+        let config = config.as_object().unwrap();
+
+        let loaded = recent_from_config(config);
+        assert_eq!(loaded, vec![recent("proj_1", "", 0)]);
+        assert!(recent_from_config(&config::ConfigMap::new()).is_empty());
     }
 
     #[test]
@@ -559,7 +921,210 @@ mod tests {
 
     #[test]
     fn test_discover_projects_error_handling() {
-        let result = discover_projects("/nonexistent/path");
+        let result = discover_projects(
+            &ProjectRoot::new("/nonexistent/path"),
+            &mut ProjectIndex::default(),
+        );
         assert!(result.is_err());
     }
+
+    fn setup_nested_projects() -> PathBuf {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let test_root = PathBuf::from(format!("/tmp/khaos_test_nested_{}", timestamp));
+        let _ = fs::remove_dir_all(&test_root);
+
+        // ClientA/Season1/pilot.kspd (depth 3) with a nested KSPD inside it
+        let pilot = test_root.join("ClientA/Season1/pilot.kspd");
+        fs::create_dir_all(pilot.join("drafts/old.kspd")).unwrap();
+        fs::write(pilot.join("manifest.json"), r#"{"title": "Pilot"}"#).unwrap();
+
+        // ClientB/feature.kspd (depth 2)
+        fs::create_dir_all(test_root.join("ClientB/feature.kspd")).unwrap();
+
+        // archive/retired.kspd (depth 2)
+        fs::create_dir_all(test_root.join("archive/retired.kspd")).unwrap();
+
+        test_root
+    }
+
+    #[test]
+    fn test_discover_projects_recurses_into_subfolders() {
+        let test_root = setup_nested_projects();
+        let root = ProjectRoot::new(test_root.to_str().unwrap());
+
+        let projects = discover_projects(&root, &mut ProjectIndex::default()).unwrap();
+
+        let mut titles: Vec<&str> = projects.iter().map(|p| p.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Pilot", "feature", "retired"]);
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_discover_projects_stops_at_kspd() {
+        let test_root = setup_nested_projects();
+        let mut root = ProjectRoot::new(test_root.to_str().unwrap());
+        root.max_depth = 10;
+
+        let projects = discover_projects(&root, &mut ProjectIndex::default()).unwrap();
+
+        assert!(projects.iter().all(|p| !p.path.ends_with("old.kspd")));
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_discover_projects_respects_max_depth() {
+        let test_root = setup_nested_projects();
+        let mut root = ProjectRoot::new(test_root.to_str().unwrap());
+        root.max_depth = 2;
+
+        let projects = discover_projects(&root, &mut ProjectIndex::default()).unwrap();
+
+        assert_eq!(projects.len(), 2);
+        assert!(projects.iter().all(|p| p.title != "Pilot"));
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_discover_projects_include_and_exclude_globs() {
+        let test_root = setup_nested_projects();
+
+        let mut root = ProjectRoot::new(test_root.to_str().unwrap());
+        root.exclude = vec!["archive".to_string()];
+        let projects = discover_projects(&root, &mut ProjectIndex::default()).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert!(projects.iter().all(|p| p.title != "retired"));
+
+        let mut root = ProjectRoot::new(test_root.to_str().unwrap());
+        root.include = vec!["ClientA/**".to_string()];
+        let projects = discover_projects(&root, &mut ProjectIndex::default()).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].title, "Pilot");
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_reachable_from_matches_the_scan() {
+        let test_root = setup_nested_projects();
+        let mut root = ProjectRoot::new(test_root.to_str().unwrap());
+        root.exclude = vec!["archive".to_string()];
+
+        let pilot = test_root.join("ClientA/Season1/pilot.kspd");
+        let feature = test_root.join("ClientB/feature.kspd");
+        let retired = test_root.join("archive/retired.kspd");
+        assert!(reachable_from(&root, &pilot));
+        assert!(reachable_from(&root, &feature));
+        // Excluded, nested in a project, not a project, or outside the root
+        assert!(!reachable_from(&root, &retired));
+        assert!(!reachable_from(&root, &pilot.join("drafts/old.kspd")));
+        assert!(!reachable_from(&root, &test_root.join("ClientA")));
+        assert!(!reachable_from(&root, Path::new("/elsewhere/x.kspd")));
+
+        root.max_depth = 2;
+        assert!(!reachable_from(&root, &pilot));
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_discover_projects_invalid_glob() {
+        let test_root = setup_nested_projects();
+        let mut root = ProjectRoot::new(test_root.to_str().unwrap());
+        root.exclude = vec!["[".to_string()];
+
+        assert!(discover_projects(&root, &mut ProjectIndex::default()).is_err());
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_discover_in_roots_reports_per_root_errors() {
+        let test_root = setup_nested_projects();
+        let roots = vec![
+            ProjectRoot::new(test_root.join("ClientA").to_str().unwrap()),
+            ProjectRoot::new(test_root.to_str().unwrap()),
+            ProjectRoot::new("/nonexistent/root"),
+        ];
+
+        let report = discover_in_roots(&roots);
+
+        // Pilot is reachable from two roots but listed once
+        assert_eq!(report.projects.len(), 3);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].root, "/nonexistent/root");
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_discover_projects_uses_index() {
+        let test_root = setup_test_projects();
+        let root = ProjectRoot::new(test_root.to_str().unwrap());
+        let proj_path = test_root.join("draft-project");
+        let path_str = proj_path.to_str().unwrap().to_string();
+
+        // An entry with a matching fingerprint is served without reading the project
+        let mut cached = read_project_metadata(&proj_path).unwrap();
+        cached.title = "From Index".to_string();
+        let mut index = ProjectIndex::default();
+        index.insert(path_str.clone(), Fingerprint::of(&proj_path), cached);
+
+        let projects = discover_projects(&root, &mut index).unwrap();
+        assert!(projects.iter().any(|p| p.title == "From Index"));
+
+        // A stale fingerprint forces a re-read and refreshes the entry
+        let mut stale = read_project_metadata(&proj_path).unwrap();
+        stale.title = "Stale".to_string();
+        index.insert(path_str.clone(), Fingerprint::default(), stale);
+
+        let projects = discover_projects(&root, &mut index).unwrap();
+        assert!(projects.iter().any(|p| p.title == "Draft Project"));
+        assert!(index.get(&path_str, &Fingerprint::of(&proj_path)).is_some());
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
+
+    #[test]
+    fn test_pick_root() {
+        let roots = vec![
+            ProjectRoot::new("/srv/scripts"),
+            ProjectRoot::new("/home/me/drafts"),
+        ];
+
+        assert_eq!(pick_root(roots.clone(), None).unwrap(), "/srv/scripts");
+        let picked = pick_root(roots.clone(), Some("/home/me/drafts/")).unwrap();
+        assert_eq!(picked, "/home/me/drafts");
+        assert!(pick_root(roots, Some("/tmp")).is_err());
+        assert!(pick_root(Vec::new(), None).is_err());
+    }
+
+    #[test]
+    fn test_indexed_project_size_follows_artifacts() {
+        let test_root = setup_test_projects();
+        let proj_path = test_root.join("draft-project");
+        let artifacts = proj_path.join("metadata/artifacts");
+        fs::create_dir_all(&artifacts).unwrap();
+        fs::write(artifacts.join("story-storage.db"), vec![0u8; 16]).unwrap();
+        let mut index = ProjectIndex::default();
+        let before = read_indexed(&mut index, &proj_path).unwrap().size_bytes;
+
+        // Growing the story database changes the fingerprint, so the cached
+        // record (and its size) is replaced
+        let fingerprint = Fingerprint::of(&proj_path);
+        fs::write(artifacts.join("story-storage.db"), vec![0u8; 1040]).unwrap();
+        assert_ne!(Fingerprint::of(&proj_path), fingerprint);
+
+        let after = read_indexed(&mut index, &proj_path).unwrap().size_bytes;
+        assert_eq!(after, before + 1024);
+
+        let _ = fs::remove_dir_all(&test_root);
+    }
 }
@@ -1,17 +1,454 @@
 // Export Service
-// Exports analysis results in JSON, Markdown, and CSV formats
+// Exports analysis results in JSON, Markdown, CSV and HTML report formats
 
-pub fn export_json(project_id: &str, scope: &str) -> Result<String, String> {
-    // TODO: Implement JSON export
-    Ok("{}".to_string())
+use crate::services::{dashboard, discovery, report};
+use crate::types::{CharacterDetail, LocationDetail, SceneDetail};
+use serde::Serialize;
+use std::path::Path;
+
+/// Which entities an export covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(crate = "serde")]
+#[serde(rename_all = "lowercase")]
+pub enum ExportScope {
+    Scenes,
+    Characters,
+    Locations,
+    All,
+}
+
+impl ExportScope {
+    pub fn parse(scope: &str) -> Result<Self, String> {
+        match scope {
+            "scenes" => Ok(ExportScope::Scenes),
+            "characters" => Ok(ExportScope::Characters),
+            "locations" => Ok(ExportScope::Locations),
+            "all" => Ok(ExportScope::All),
+            _ => Err(format!("Unknown export scope: {}", scope)),
+        }
+    }
+
+    fn includes(self, section: ExportScope) -> bool {
+        self == ExportScope::All || self == section
+    }
+}
+
+/// Project entities with their saved analyses. Sections outside the export
+/// scope are None.
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "serde")]
+pub struct ExportData {
+    pub title: String,
+    pub project_path: String,
+    pub scope: ExportScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scenes: Option<Vec<SceneDetail>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub characters: Option<Vec<CharacterDetail>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locations: Option<Vec<LocationDetail>>,
+}
+
+/// Gather summaries and saved analyses for the scope. Analysis is only
+/// queried for entities listed in the project's analysis index.
+pub async fn load_export_data(kspd_path: &str, scope: ExportScope) -> Result<ExportData, String> {
+    let project = discovery::read_project_metadata(Path::new(kspd_path))?;
+    let index = dashboard::scan_analysis_index(kspd_path);
+
+    let mut data = ExportData {
+        title: project.title,
+        project_path: kspd_path.to_string(),
+        scope,
+        scenes: None,
+        characters: None,
+        locations: None,
+    };
+
+    if scope.includes(ExportScope::Scenes) {
+        let mut details = Vec::new();
+        for summary in dashboard::query_scenes(kspd_path).await? {
+            let analysis = if index.scenes.contains(&summary.id) {
+                dashboard::query_scene_analysis(kspd_path, &summary.id).await?
+            } else {
+                None
+            };
+            details.push(SceneDetail { summary, analysis });
+        }
+        data.scenes = Some(details);
+    }
+
+    if scope.includes(ExportScope::Characters) {
+        let mut details = Vec::new();
+        for summary in dashboard::query_characters(kspd_path).await? {
+            let analysis = if index.characters.contains(&summary.id) {
+                dashboard::query_character_analysis(kspd_path, &summary.id).await?
+            } else {
+                None
+            };
+            details.push(CharacterDetail { summary, analysis });
+        }
+        data.characters = Some(details);
+    }
+
+    if scope.includes(ExportScope::Locations) {
+        let mut details = Vec::new();
+        for summary in dashboard::query_locations(kspd_path).await? {
+            let analysis = if index.locations.contains(&summary.id) {
+                dashboard::query_location_analysis(kspd_path, &summary.id).await?
+            } else {
+                None
+            };
+            details.push(LocationDetail { summary, analysis });
+        }
+        data.locations = Some(details);
+    }
+
+    Ok(data)
 }
 
-pub fn export_markdown(project_id: &str, scope: &str) -> Result<String, String> {
-    // TODO: Implement Markdown export
-    Ok("# Results".to_string())
+/// Render export data in the named format ("json", "markdown", "csv" or "html")
+pub fn render(data: &ExportData, format: &str) -> Result<String, String> {
+    match format {
+        "json" => export_json(data),
+        "markdown" | "md" => export_markdown(data),
+        "csv" => export_csv(data),
+        "html" => report::export_html(data),
+        _ => Err(format!("Unknown export format: {}", format)),
+    }
 }
 
-pub fn export_csv(project_id: &str, scope: &str) -> Result<String, String> {
-    // TODO: Implement CSV export
-    Ok("".to_string())
+pub fn export_json(data: &ExportData) -> Result<String, String> {
+    serde_json::to_string_pretty(data).map_err(|e| format!("Failed to serialize export: {}", e))
+}
+
+pub fn export_markdown(data: &ExportData) -> Result<String, String> {
+    let mut md = format!(
+        "# {}\n\nAnalysis export for `{}`\n",
+        data.title, data.project_path
+    );
+
+    if let Some(scenes) = &data.scenes {
+        md.push_str("\n## Scenes\n");
+        for scene in scenes {
+            let s = &scene.summary;
+            md.push_str(&format!("\n### {}. {}\n\n", s.index, s.slugline));
+            push_item(&mut md, "Duration", &s.duration);
+            push_item(&mut md, "Words", &s.word_count.to_string());
+            push_item(&mut md, "Characters", &s.characters.join(", "));
+
+            if let Some(a) = &scene.analysis {
+                push_opt(&mut md, "Summary", &a.summary);
+                push_opt(&mut md, "Narrative role", &a.narrative_role);
+                push_opt(&mut md, "Emotional tone", &a.emotional_tone);
+                push_opt(&mut md, "Stakes", &a.stakes);
+                push_item(&mut md, "Themes", &a.themes.join(", "));
+                push_list(&mut md, "Plot beats", &a.plot_beats);
+                push_list(&mut md, "Strengths", &a.strengths);
+                push_list(&mut md, "Development areas", &a.development_areas);
+            } else {
+                md.push_str("\n_Not analyzed_\n");
+            }
+        }
+    }
+
+    if let Some(characters) = &data.characters {
+        md.push_str("\n## Characters\n");
+        for character in characters {
+            let c = &character.summary;
+            md.push_str(&format!("\n### {}\n\n", c.name));
+            push_item(&mut md, "Scenes", &c.scene_count.to_string());
+            push_item(&mut md, "Dialogue lines", &c.dialogue_lines.to_string());
+            push_item(&mut md, "Dialogue share", &format!("{:.1}%", c.percentage));
+
+            if let Some(a) = &character.analysis {
+                push_opt(&mut md, "Summary", &a.summary);
+                push_opt(&mut md, "Narrative role", &a.narrative_role);
+                push_opt(&mut md, "Arc", &a.arc);
+                push_item(&mut md, "Traits", &a.traits.join(", "));
+                push_list(&mut md, "Goals", &a.goals);
+                push_list(&mut md, "Key turning points", &a.key_turning_points);
+            } else {
+                md.push_str("\n_Not analyzed_\n");
+            }
+        }
+    }
+
+    if let Some(locations) = &data.locations {
+        md.push_str("\n## Locations\n");
+        for location in locations {
+            let l = &location.summary;
+            md.push_str(&format!("\n### {}\n\n", l.name));
+            push_item(&mut md, "Scenes", &l.scene_count.to_string());
+            push_item(&mut md, "Pages", &l.page_count.to_string());
+
+            if let Some(a) = &location.analysis {
+                push_opt(&mut md, "Summary", &a.summary);
+                push_opt(&mut md, "Atmosphere", &a.atmosphere);
+                push_opt(&mut md, "Significance", &a.significance);
+                push_item(&mut md, "Themes", &a.themes.join(", "));
+            } else {
+                md.push_str("\n_Not analyzed_\n");
+            }
+        }
+    }
+
+    Ok(md)
+}
+
+/// "- **Label:** value" (skipped when empty)
+fn push_item(md: &mut String, label: &str, value: &str) {
+    if !value.trim().is_empty() {
+        md.push_str(&format!("- **{}:** {}\n", label, value));
+    }
+}
+
+fn push_opt(md: &mut String, label: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        push_item(md, label, value);
+    }
+}
+
+/// Bulleted sub-list under a bold label (skipped when empty)
+fn push_list(md: &mut String, label: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    md.push_str(&format!("- **{}:**\n", label));
+    for item in items {
+        md.push_str(&format!("  - {}\n", item));
+    }
+}
+
+/// Columns shared by every entity type; cells that do not apply are empty
+const CSV_HEADER: &[&str] = &[
+    "type",
+    "id",
+    "name",
+    "index",
+    "scene_count",
+    "words",
+    "lines",
+    "dialogue_share",
+    "pages",
+    "duration",
+    "characters",
+    "analyzed",
+    "summary",
+    "narrative_role",
+    "themes",
+];
+
+pub fn export_csv(data: &ExportData) -> Result<String, String> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+
+    for scene in data.scenes.iter().flatten() {
+        let s = &scene.summary;
+        let a = scene.analysis.as_ref();
+        rows.push(vec![
+            "scene".to_string(),
+            s.id.clone(),
+            s.slugline.clone(),
+            s.index.to_string(),
+            String::new(),
+            s.word_count.to_string(),
+            s.line_count.to_string(),
+            String::new(),
+            a.and_then(|a| a.length_pages.clone()).unwrap_or_default(),
+            s.duration.clone(),
+            s.characters.join("; "),
+            yes_no(a.is_some()),
+            a.and_then(|a| a.summary.clone()).unwrap_or_default(),
+            a.and_then(|a| a.narrative_role.clone()).unwrap_or_default(),
+            a.map(|a| a.themes.join("; ")).unwrap_or_default(),
+        ]);
+    }
+
+    for character in data.characters.iter().flatten() {
+        let c = &character.summary;
+        let a = character.analysis.as_ref();
+        rows.push(vec![
+            "character".to_string(),
+            c.id.clone(),
+            c.name.clone(),
+            String::new(),
+            c.scene_count.to_string(),
+            c.words.to_string(),
+            c.dialogue_lines.to_string(),
+            format!("{:.1}", c.percentage),
+            String::new(),
+            String::new(),
+            String::new(),
+            yes_no(a.is_some()),
+            a.and_then(|a| a.summary.clone()).unwrap_or_default(),
+            a.and_then(|a| a.narrative_role.clone()).unwrap_or_default(),
+            a.map(|a| a.themes.join("; ")).unwrap_or_default(),
+        ]);
+    }
+
+    for location in data.locations.iter().flatten() {
+        let l = &location.summary;
+        let a = location.analysis.as_ref();
+        rows.push(vec![
+            "location".to_string(),
+            l.id.clone(),
+            l.name.clone(),
+            String::new(),
+            l.scene_count.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            l.page_count.to_string(),
+            String::new(),
+            String::new(),
+            yes_no(a.is_some()),
+            a.and_then(|a| a.summary.clone()).unwrap_or_default(),
+            a.and_then(|a| a.narrative_role.clone()).unwrap_or_default(),
+            a.map(|a| a.themes.join("; ")).unwrap_or_default(),
+        ]);
+    }
+
+    let mut csv = csv_line(CSV_HEADER.iter().map(|h| h.to_string()));
+    for row in rows {
+        csv.push_str(&csv_line(row));
+    }
+    Ok(csv)
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+/// One CSV record (RFC 4180 quoting), terminated by CRLF
+pub(crate) fn csv_line(cells: impl IntoIterator<Item = String>) -> String {
+    let cells: Vec<String> = cells.into_iter().map(|cell| csv_escape(&cell)).collect();
+    format!("{}\r\n", cells.join(","))
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        CharacterAnalysis, CharacterSummary, LocationSummary, SceneAnalysis, SceneSummary,
+    };
+
+    fn sample_data(scope: ExportScope) -> ExportData {
+        let scene = SceneDetail {
+            summary: SceneSummary {
+                id: "scn_001".to_string(),
+                index: 1,
+                slugline: "INT. VAULT - NIGHT".to_string(),
+                duration: "2:30".to_string(),
+                word_count: 120,
+                line_count: 14,
+                characters: vec!["MAYA".to_string(), "LEO".to_string()],
+            },
+            analysis: Some(SceneAnalysis {
+                summary: Some("Maya cracks the vault, \"finally\".".to_string()),
+                narrative_role: Some("Climax".to_string()),
+                plot_beats: vec!["Alarm trips".to_string()],
+                themes: vec!["trust".to_string(), "greed".to_string()],
+                ..Default::default()
+            }),
+        };
+        let character = CharacterDetail {
+            summary: CharacterSummary {
+                id: "chr_001".to_string(),
+                name: "MAYA".to_string(),
+                dialogue_lines: 40,
+                words: 512,
+                scene_count: 12,
+                percentage: 41.5,
+            },
+            analysis: Some(CharacterAnalysis {
+                arc: Some("From loner to leader".to_string()),
+                ..Default::default()
+            }),
+        };
+        let location = LocationDetail {
+            summary: LocationSummary {
+                id: "loc_001".to_string(),
+                name: "VAULT".to_string(),
+                scene_count: 3,
+                page_count: 5,
+            },
+            analysis: None,
+        };
+
+        ExportData {
+            title: "The Heist".to_string(),
+            project_path: "/p/heist.kspd".to_string(),
+            scope,
+            scenes: scope.includes(ExportScope::Scenes).then(|| vec![scene]),
+            characters: scope
+                .includes(ExportScope::Characters)
+                .then(|| vec![character]),
+            locations: scope
+                .includes(ExportScope::Locations)
+                .then(|| vec![location]),
+        }
+    }
+
+    #[test]
+    fn test_export_scope_parse() {
+        assert_eq!(ExportScope::parse("all").unwrap(), ExportScope::All);
+        assert_eq!(ExportScope::parse("scenes").unwrap(), ExportScope::Scenes);
+        assert!(ExportScope::parse("props").is_err());
+    }
+
+    #[test]
+    fn test_export_json_omits_sections_outside_scope() {
+        let json = export_json(&sample_data(ExportScope::Characters)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["scope"], "characters");
+        assert_eq!(value["characters"][0]["summary"]["name"], "MAYA");
+        assert!(value.get("scenes").is_none());
+        assert!(value.get("locations").is_none());
+    }
+
+    #[test]
+    fn test_export_markdown_sections() {
+        let md = export_markdown(&sample_data(ExportScope::All)).unwrap();
+
+        assert!(md.starts_with("# The Heist\n"));
+        assert!(md.contains("## Scenes\n\n### 1. INT. VAULT - NIGHT\n"));
+        assert!(md.contains("- **Narrative role:** Climax\n"));
+        assert!(md.contains("- **Plot beats:**\n  - Alarm trips\n"));
+        assert!(md.contains("- **Dialogue share:** 41.5%\n"));
+        assert!(md.contains("- **Arc:** From loner to leader\n"));
+        assert!(md.contains("### VAULT\n\n- **Scenes:** 3\n- **Pages:** 5\n\n_Not analyzed_\n"));
+    }
+
+    #[test]
+    fn test_export_csv_rows_and_quoting() {
+        let csv = export_csv(&sample_data(ExportScope::All)).unwrap();
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(lines.len(), 5); // header, 3 rows, trailing empty
+        assert!(lines[1].starts_with("scene,scn_001,INT. VAULT - NIGHT,1,,120,14,"));
+        assert!(lines[1].contains(",\"Maya cracks the vault, \"\"finally\"\".\","));
+        assert!(lines[1].ends_with(",Climax,trust; greed"));
+        assert!(lines[2].starts_with("character,chr_001,MAYA,,12,512,40,41.5,"));
+        assert!(lines[3].starts_with("location,loc_001,VAULT,,3,,,,5,"));
+        assert!(lines[3].contains(",no,"));
+    }
+
+    #[test]
+    fn test_render_unknown_format() {
+        let data = sample_data(ExportScope::All);
+        assert!(render(&data, "csv").is_ok());
+        assert_eq!(
+            render(&data, "pdf").unwrap_err(),
+            "Unknown export format: pdf"
+        );
+    }
 }
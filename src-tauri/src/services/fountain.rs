@@ -0,0 +1,350 @@
+// Fountain Service
+// Exports the screenplay as Fountain with saved scene analysis woven in under
+// each scene heading, as notes ([[ ... ]]) or boneyard (/* ... */) blocks
+
+use crate::services::{discovery, export};
+use crate::types::{SceneAnalysis, SceneDetail};
+use std::path::Path;
+
+/// How analysis is embedded in the Fountain output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationStyle {
+    /// `[[ ... ]]` notes, shown by most editors alongside the script
+    Notes,
+    /// `/* ... */` boneyard, hidden from rendered output
+    Boneyard,
+}
+
+impl AnnotationStyle {
+    pub fn parse(style: &str) -> Result<Self, String> {
+        match style {
+            "notes" => Ok(AnnotationStyle::Notes),
+            "boneyard" => Ok(AnnotationStyle::Boneyard),
+            _ => Err(format!("Unknown annotation style: {}", style)),
+        }
+    }
+}
+
+/// Build annotated Fountain for a project. With `source_path` (the original
+/// .fountain file) annotations are inserted into the full script; otherwise
+/// the output is an outline of scene headings and their annotations.
+pub async fn export_fountain(
+    kspd_path: &str,
+    style: AnnotationStyle,
+    source_path: Option<&str>,
+) -> Result<String, String> {
+    let data = export::load_export_data(kspd_path, export::ExportScope::Scenes).await?;
+    let mut scenes = data.scenes.unwrap_or_default();
+    scenes.sort_by_key(|scene| scene.summary.index);
+
+    match source_path {
+        Some(source_path) => {
+            let source = std::fs::read_to_string(source_path)
+                .map_err(|e| format!("Failed to read Fountain source: {}", e))?;
+            Ok(annotate_source(&source, &scenes, style))
+        }
+        None => {
+            let project = discovery::read_project_metadata(Path::new(kspd_path))?;
+            Ok(outline(
+                &project.title,
+                project.author.as_deref(),
+                &scenes,
+                style,
+            ))
+        }
+    }
+}
+
+/// Insert each scene's annotation after the matching scene heading. Headings
+/// are matched to scenes by normalized slugline, in order; headings with no
+/// matching scene are left unannotated.
+pub fn annotate_source(source: &str, scenes: &[SceneDetail], style: AnnotationStyle) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut next_scene = 0;
+    let mut headings = 0;
+    let mut previous_blank = true;
+    let mut in_boneyard = false;
+
+    for line in source.lines() {
+        out.push_str(line);
+        out.push('\n');
+
+        let trimmed = line.trim();
+        let is_heading = previous_blank && !in_boneyard && is_scene_heading(trimmed);
+        if trimmed.contains("/*") {
+            in_boneyard = true;
+        }
+        if trimmed.contains("*/") {
+            in_boneyard = false;
+        }
+        previous_blank = trimmed.is_empty();
+
+        if !is_heading {
+            continue;
+        }
+        headings += 1;
+
+        // Scenes are only consumed forward, so repeated sluglines pair up in order
+        let slugline = normalize_slugline(trimmed);
+        let Some(offset) = scenes[next_scene..]
+            .iter()
+            .position(|scene| normalize_slugline(&scene.summary.slugline) == slugline)
+        else {
+            continue;
+        };
+        let scene = &scenes[next_scene + offset];
+        next_scene += offset + 1;
+
+        if let Some(annotation) = scene
+            .analysis
+            .as_ref()
+            .and_then(|analysis| annotation(analysis, style))
+        {
+            out.push('\n');
+            out.push_str(&annotation);
+            out.push('\n');
+        }
+    }
+
+    if headings != scenes.len() {
+        tracing::warn!(
+            "Fountain source has {} scene headings but the project has {} scenes; unmatched headings are not annotated",
+            headings,
+            scenes.len()
+        );
+    }
+
+    out
+}
+
+/// Title page plus one heading and annotation per scene
+pub fn outline(
+    title: &str,
+    author: Option<&str>,
+    scenes: &[SceneDetail],
+    style: AnnotationStyle,
+) -> String {
+    let mut out = format!("Title: {}\n", title);
+    if let Some(author) = author {
+        out.push_str(&format!("Author: {}\n", author));
+    }
+
+    for scene in scenes {
+        let slugline = scene.summary.slugline.trim();
+        out.push('\n');
+        // Headings without an INT/EXT prefix must be forced with a leading '.'
+        if !is_scene_heading(slugline) {
+            out.push('.');
+        }
+        out.push_str(slugline);
+        out.push('\n');
+
+        if let Some(annotation) = scene
+            .analysis
+            .as_ref()
+            .and_then(|analysis| annotation(analysis, style))
+        {
+            out.push('\n');
+            out.push_str(&annotation);
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Summary, plot beats, stakes and development areas as a single note or
+/// boneyard block (None when the analysis has none of them)
+fn annotation(analysis: &SceneAnalysis, style: AnnotationStyle) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(summary) = analysis.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        lines.push(format!("Summary: {}", summary.trim()));
+    }
+    push_list(&mut lines, "Plot beats", &analysis.plot_beats);
+    if let Some(stakes) = analysis.stakes.as_deref().filter(|s| !s.trim().is_empty()) {
+        lines.push(format!("Stakes: {}", stakes.trim()));
+    }
+    push_list(&mut lines, "Development areas", &analysis.development_areas);
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    // Blank lines would end a note early, and the closing marker would end
+    // the block; neither may appear inside the annotation
+    let body: Vec<String> = lines
+        .iter()
+        .flat_map(|line| line.lines())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match style {
+            AnnotationStyle::Notes => line.replace("]]", "] ]"),
+            AnnotationStyle::Boneyard => line.replace("*/", "* /"),
+        })
+        .collect();
+
+    Some(match style {
+        AnnotationStyle::Notes => format!("[[Analysis\n{}]]", body.join("\n")),
+        AnnotationStyle::Boneyard => format!("/*\nAnalysis\n{}\n*/", body.join("\n")),
+    })
+}
+
+fn push_list(lines: &mut Vec<String>, label: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    lines.push(format!("{}:", label));
+    for item in items {
+        lines.push(format!("- {}", item.trim()));
+    }
+}
+
+/// Fountain scene heading: INT/EXT/EST/I/E prefix followed by '.' or a space,
+/// or a forced heading starting with a single '.'
+fn is_scene_heading(line: &str) -> bool {
+    if line.starts_with('.') {
+        return line.len() > 1 && !line.starts_with("..");
+    }
+
+    let upper = line.to_ascii_uppercase();
+    ["INT./EXT", "INT/EXT", "I/E", "INT", "EXT", "EST"]
+        .iter()
+        .any(|prefix| {
+            upper.starts_with(prefix)
+                && matches!(upper[prefix.len()..].chars().next(), Some('.') | Some(' '))
+        })
+}
+
+/// Slugline in comparable form: no forcing '.', no trailing scene number
+/// (`#12A#`), single spaces, upper case
+fn normalize_slugline(slugline: &str) -> String {
+    let mut slugline = slugline.trim();
+    if let Some(rest) = slugline.strip_prefix('.') {
+        slugline = rest;
+    }
+    if let Some(rest) = slugline.strip_suffix('#') {
+        if let Some(start) = rest.rfind('#') {
+            slugline = &rest[..start];
+        }
+    }
+    slugline
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SceneSummary;
+
+    fn scene(index: usize, slugline: &str, analysis: Option<SceneAnalysis>) -> SceneDetail {
+        SceneDetail {
+            summary: SceneSummary {
+                id: format!("scn_{:03}", index),
+                index,
+                slugline: slugline.to_string(),
+                duration: String::new(),
+                word_count: 0,
+                line_count: 0,
+                characters: Vec::new(),
+            },
+            analysis,
+        }
+    }
+
+    fn analysis() -> SceneAnalysis {
+        SceneAnalysis {
+            summary: Some("Maya opens the vault.".to_string()),
+            plot_beats: vec!["Alarm trips".to_string(), "Leo runs".to_string()],
+            stakes: Some("Everything [[on]] the line".to_string()),
+            development_areas: vec!["Tighten the exit".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_scene_heading() {
+        assert!(is_scene_heading("INT. VAULT - NIGHT"));
+        assert!(is_scene_heading("int/ext car - day"));
+        assert!(is_scene_heading(".FLASHBACK"));
+        assert!(!is_scene_heading("INTERIOR DESIGN"));
+        assert!(!is_scene_heading("...and then"));
+        assert!(!is_scene_heading("MAYA"));
+    }
+
+    #[test]
+    fn test_annotate_source_inserts_notes_under_headings() {
+        let source = "Title: Heist\n\nINT. VAULT - NIGHT\n\nMaya works the dial.\n\nEXT. ROOF - DAWN\n\nWind.\n";
+        let scenes = vec![
+            scene(1, "INT. VAULT - NIGHT", Some(analysis())),
+            scene(2, "EXT. ROOF - DAWN", None),
+        ];
+
+        let out = annotate_source(source, &scenes, AnnotationStyle::Notes);
+        assert_eq!(
+            out,
+            "Title: Heist\n\nINT. VAULT - NIGHT\n\n[[Analysis\nSummary: Maya opens the vault.\nPlot beats:\n- Alarm trips\n- Leo runs\nStakes: Everything [[on] ] the line\nDevelopment areas:\n- Tighten the exit]]\n\nMaya works the dial.\n\nEXT. ROOF - DAWN\n\nWind.\n"
+        );
+    }
+
+    #[test]
+    fn test_annotate_source_skips_headings_in_boneyard() {
+        let source = "/*\nINT. CUT SCENE - DAY\n*/\n\nINT. VAULT - NIGHT\n\nAction.\n";
+        let scenes = vec![scene(1, "INT. VAULT - NIGHT", Some(analysis()))];
+
+        let out = annotate_source(source, &scenes, AnnotationStyle::Boneyard);
+        assert!(
+            out.contains("INT. VAULT - NIGHT\n\n/*\nAnalysis\nSummary: Maya opens the vault.\n")
+        );
+        assert!(out.contains("INT. CUT SCENE - DAY\n*/\n\nINT. VAULT"));
+    }
+
+    #[test]
+    fn test_annotate_source_matches_headings_by_slugline() {
+        // The source has a heading the project doesn't, and the project has a
+        // scene the source dropped
+        let source = "INT. LOBBY - DAY\n\nWaiting.\n\n.int.  vault - night #2#\n\nAction.\n";
+        let scenes = vec![
+            scene(1, "EXT. STREET - DAY", Some(analysis())),
+            scene(2, "INT. VAULT - NIGHT", Some(analysis())),
+        ];
+
+        let out = annotate_source(source, &scenes, AnnotationStyle::Notes);
+        assert!(out.starts_with("INT. LOBBY - DAY\n\nWaiting.\n\n.int."));
+        assert!(out.contains("#2#\n\n[[Analysis\nSummary: Maya opens the vault."));
+        assert_eq!(out.matches("[[Analysis").count(), 1);
+    }
+
+    #[test]
+    fn test_normalize_slugline() {
+        assert_eq!(
+            normalize_slugline(" .int.  vault - night #12A#"),
+            "INT. VAULT - NIGHT"
+        );
+        assert_eq!(normalize_slugline("MONTAGE"), "MONTAGE");
+    }
+
+    #[test]
+    fn test_outline_forces_nonstandard_headings() {
+        let scenes = vec![
+            scene(1, "MONTAGE", None),
+            scene(2, "INT. VAULT - NIGHT", Some(analysis())),
+        ];
+
+        let out = outline("Heist", Some("A. Writer"), &scenes, AnnotationStyle::Notes);
+        assert!(out.starts_with(
+            "Title: Heist\nAuthor: A. Writer\n\n.MONTAGE\n\nINT. VAULT - NIGHT\n\n[[Analysis\n"
+        ));
+        assert!(out.ends_with("- Tighten the exit]]\n"));
+    }
+
+    #[test]
+    fn test_annotation_style_parse() {
+        assert_eq!(
+            AnnotationStyle::parse("notes").unwrap(),
+            AnnotationStyle::Notes
+        );
+        assert!(AnnotationStyle::parse("comments").is_err());
+    }
+}
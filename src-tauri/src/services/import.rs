@@ -134,7 +134,7 @@ pub async fn execute_parse(
     output_path: &str,
 ) -> Result<ImportResult, String> {
     // Locate khaos-tools binary
-    let cli_path = find_khaos_tools()?;
+    let cli_path = crate::services::config::find_binary("khaos-tools")?;
 
     tracing::info!(
         "Starting parse: {} -> {} (request: {})",
@@ -245,47 +245,6 @@ pub async fn execute_parse(
     Ok(result)
 }
 
-/// Find khaos-tools binary via PATH or config override.
-fn find_khaos_tools() -> Result<String, String> {
-    // Check config override first (shared TUI config)
-    if let Ok(path) = load_khaos_tools_from_config() {
-        if Path::new(&path).exists() {
-            return Ok(path);
-        }
-    }
-
-    // Try PATH lookup
-    if let Ok(output) = std::process::Command::new("which")
-        .arg("khaos-tools")
-        .output()
-    {
-        if output.status.success() {
-            let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            if !path.is_empty() {
-                return Ok(path);
-            }
-        }
-    }
-
-    Err("khaos-tools not found. Ensure it is installed and available on PATH.".to_string())
-}
-
-/// Load khaos_tools_path from shared TUI config
-fn load_khaos_tools_from_config() -> Result<String, String> {
-    let home = std::env::var("HOME").map_err(|_| "HOME not set".to_string())?;
-    let config_path = format!("{}/.config/khaos-tui/config.json", home);
-
-    let content = std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let config: serde_json::Map<String, serde_json::Value> =
-        serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
-    if let Some(serde_json::Value::String(path)) = config.get("khaos_tools_path") {
-        Ok(path.clone())
-    } else {
-        Err("No khaos_tools_path in config".to_string())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,17 +1,520 @@
 // Keychain Service
-// Secure storage of API keys and credentials
+// Secure storage of API keys and credentials. Uses the Linux Secret Service
+// (via secret-tool) when available, otherwise an AES-256-GCM encrypted file
+// in the config directory, unlocked with a passphrase for the session.
+
+use crate::services::config;
+use crate::types::SecretStoreStatus;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// Secret Service attribute identifying this app's entries
+const SERVICE: &str = "khaos-ui";
+const SECRETS_FILE: &str = "secrets.json";
+const FILE_VERSION: u32 = 1;
+const KDF_ITERATIONS: u32 = 600_000;
+
+/// Where secrets are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    SecretService,
+    EncryptedFile,
+}
+
+impl Backend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Backend::SecretService => "secret-service",
+            Backend::EncryptedFile => "encrypted-file",
+        }
+    }
+}
+
+/// Secret Service is used when secret-tool is installed and a session bus is
+/// available (it is not, e.g., over plain SSH or in containers)
+pub fn backend() -> Backend {
+    static BACKEND: OnceLock<Backend> = OnceLock::new();
+    *BACKEND.get_or_init(|| {
+        let available = cfg!(target_os = "linux")
+            && std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some()
+            && Command::new("which")
+                .arg("secret-tool")
+                .output()
+                .map(|output| output.status.success())
+                .unwrap_or(false);
+        if available {
+            Backend::SecretService
+        } else {
+            Backend::EncryptedFile
+        }
+    })
+}
+
+/// Secret name for a provider's API key
+pub fn provider_key(provider_id: &str) -> String {
+    format!("provider:{}", provider_id)
+}
 
 pub fn store_secret(key: &str, value: &str) -> Result<(), String> {
-    // TODO: Use platform keychain (macOS Keychain, Windows Credential Manager, Linux Secret Service)
-    Ok(())
+    match backend() {
+        Backend::SecretService => secret_tool_store(key, value),
+        Backend::EncryptedFile => with_session(|session| {
+            let file = EncryptedFile::default_location()?;
+            let mut secrets = file.read(session)?;
+            secrets.insert(key.to_string(), value.to_string());
+            file.write(session, &secrets)
+        }),
+    }
 }
 
 pub fn retrieve_secret(key: &str) -> Result<Option<String>, String> {
-    // TODO: Retrieve from platform keychain
-    Ok(None)
+    match backend() {
+        Backend::SecretService => secret_tool_lookup(key),
+        Backend::EncryptedFile => {
+            let file = EncryptedFile::default_location()?;
+            if !file.exists() {
+                return Ok(None);
+            }
+            with_session(|session| Ok(file.read(session)?.remove(key)))
+        }
+    }
 }
 
 pub fn delete_secret(key: &str) -> Result<(), String> {
-    // TODO: Delete from platform keychain
+    match backend() {
+        Backend::SecretService => secret_tool_clear(key),
+        Backend::EncryptedFile => {
+            let file = EncryptedFile::default_location()?;
+            if !file.exists() {
+                return Ok(());
+            }
+            with_session(|session| {
+                let mut secrets = file.read(session)?;
+                if secrets.remove(key).is_some() {
+                    file.write(session, &secrets)?;
+                }
+                Ok(())
+            })
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Session passphrase (encrypted file backend)
+// ---------------------------------------------------------------------------
+
+/// The unlocked store: the passphrase and the last key derived from it, so
+/// reads and writes while unlocked don't repeat the KDF
+struct Session {
+    passphrase: String,
+    derived: Option<DerivedKey>,
+}
+
+struct DerivedKey {
+    salt: Vec<u8>,
+    iterations: u32,
+    key: [u8; 32],
+}
+
+impl Session {
+    fn new(passphrase: &str) -> Self {
+        Session {
+            passphrase: passphrase.to_string(),
+            derived: None,
+        }
+    }
+
+    /// Cipher for a file with `salt` and `iterations`, deriving the key only
+    /// when they differ from the cached one
+    fn cipher(&mut self, salt: &[u8], iterations: u32) -> Aes256Gcm {
+        let cached = self
+            .derived
+            .as_ref()
+            .filter(|d| d.salt == salt && d.iterations == iterations);
+        let key = match cached {
+            Some(derived) => derived.key,
+            None => {
+                let key = derive_key(&self.passphrase, salt, iterations);
+                self.derived = Some(DerivedKey {
+                    salt: salt.to_vec(),
+                    iterations,
+                    key,
+                });
+                key
+            }
+        };
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+    }
+
+    /// Salt for the next write: the cached key's, so it can be reused (every
+    /// write still gets a fresh nonce), else a new random salt
+    fn write_salt(&self, iterations: u32) -> Vec<u8> {
+        match self.derived.as_ref().filter(|d| d.iterations == iterations) {
+            Some(derived) => derived.salt.clone(),
+            None => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                salt.to_vec()
+            }
+        }
+    }
+}
+
+fn session_slot() -> &'static Mutex<Option<Session>> {
+    static SESSION: OnceLock<Mutex<Option<Session>>> = OnceLock::new();
+    SESSION.get_or_init(|| Mutex::new(None))
+}
+
+/// Run `f` with the unlocked session, holding it for the whole read/write
+fn with_session<T>(f: impl FnOnce(&mut Session) -> Result<T, String>) -> Result<T, String> {
+    let mut slot = session_slot()
+        .lock()
+        .map_err(|_| "Secret store lock poisoned".to_string())?;
+    let session = slot
+        .as_mut()
+        .ok_or_else(|| "Secret store is locked. Enter the passphrase in Settings.".to_string())?;
+    f(session)
+}
+
+/// Unlock the encrypted file for this session. An existing file must decrypt
+/// with the passphrase; otherwise the passphrase is used to create it on the
+/// first store.
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    if backend() == Backend::SecretService {
+        return Ok(());
+    }
+    if passphrase.is_empty() {
+        return Err("Passphrase cannot be empty".to_string());
+    }
+
+    // Reading the existing file checks the passphrase and caches its key
+    let mut session = Session::new(passphrase);
+    let file = EncryptedFile::default_location()?;
+    if file.exists() {
+        file.read(&mut session)?;
+    }
+
+    *session_slot()
+        .lock()
+        .map_err(|_| "Secret store lock poisoned".to_string())? = Some(session);
+    tracing::info!("Secret store unlocked");
     Ok(())
 }
+
+/// Forget the session passphrase and its derived key
+pub fn lock() {
+    if let Ok(mut slot) = session_slot().lock() {
+        *slot = None;
+    }
+}
+
+pub fn status() -> Result<SecretStoreStatus, String> {
+    let backend = backend();
+    let (locked, initialized) = match backend {
+        Backend::SecretService => (false, true),
+        Backend::EncryptedFile => {
+            let unlocked = session_slot()
+                .lock()
+                .map(|slot| slot.is_some())
+                .unwrap_or(false);
+            (!unlocked, EncryptedFile::default_location()?.exists())
+        }
+    };
+
+    Ok(SecretStoreStatus {
+        backend: backend.as_str().to_string(),
+        locked,
+        initialized,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Secret Service via secret-tool (libsecret)
+// ---------------------------------------------------------------------------
+
+fn secret_tool_store(key: &str, value: &str) -> Result<(), String> {
+    let mut child = Command::new("secret-tool")
+        .args([
+            "store",
+            &format!("--label=Khaos UI: {}", key),
+            "service",
+            SERVICE,
+            "account",
+            key,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+
+    // The secret is passed on stdin so it never appears in the process list
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(value.as_bytes())
+            .map_err(|e| format!("Failed to write to secret-tool: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for secret-tool: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to store secret: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn secret_tool_lookup(key: &str) -> Result<Option<String>, String> {
+    let output = Command::new("secret-tool")
+        .args(["lookup", "service", SERVICE, "account", key])
+        .output()
+        .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+
+    // secret-tool exits non-zero with no output when nothing matches
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let value =
+        String::from_utf8(output.stdout).map_err(|e| format!("Failed to read secret: {}", e))?;
+    Ok(Some(value).filter(|v| !v.is_empty()))
+}
+
+fn secret_tool_clear(key: &str) -> Result<(), String> {
+    let output = Command::new("secret-tool")
+        .args(["clear", "service", SERVICE, "account", key])
+        .output()
+        .map_err(|e| format!("Failed to run secret-tool: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to delete secret: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Encrypted file
+// ---------------------------------------------------------------------------
+
+/// On-disk format: the secrets map as JSON, encrypted with AES-256-GCM under
+/// a key derived from the passphrase with PBKDF2-HMAC-SHA256
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "serde")]
+struct SecretsFile {
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct EncryptedFile {
+    path: PathBuf,
+    /// KDF iterations for newly written files (existing files record their own)
+    iterations: u32,
+}
+
+impl EncryptedFile {
+    fn default_location() -> Result<Self, String> {
+        Ok(EncryptedFile {
+            path: config::data_file(SECRETS_FILE)?,
+            iterations: KDF_ITERATIONS,
+        })
+    }
+
+    fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    fn read(&self, session: &mut Session) -> Result<BTreeMap<String, String>, String> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(format!("Failed to read secret store: {}", e)),
+        };
+
+        let file: SecretsFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse secret store: {}", e))?;
+        if file.version != FILE_VERSION {
+            return Err(format!(
+                "Unsupported secret store version: {}",
+                file.version
+            ));
+        }
+
+        let decode = |field: &str| {
+            hex::decode(field).map_err(|e| format!("Failed to parse secret store: {}", e))
+        };
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        let ciphertext = decode(&file.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err("Failed to parse secret store: invalid nonce".to_string());
+        }
+
+        let plaintext = session
+            .cipher(&salt, file.iterations)
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Incorrect passphrase or corrupted secret store".to_string())?;
+
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Failed to parse secret store: {}", e))
+    }
+
+    fn write(
+        &self,
+        session: &mut Session,
+        secrets: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        let salt = session.write_salt(self.iterations);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+        let plaintext = serde_json::to_vec(secrets)
+            .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+        let ciphertext = session
+            .cipher(&salt, self.iterations)
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| "Failed to encrypt secrets".to_string())?;
+
+        let file = SecretsFile {
+            version: FILE_VERSION,
+            iterations: self.iterations,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize secret store: {}", e))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+
+        // Write owner-only, then rename into place
+        let tmp_path = self.path.with_extension("json.tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let result = options
+            .open(&tmp_path)
+            .and_then(|mut f| {
+                f.write_all(json.as_bytes())?;
+                f.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp_path, &self.path));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(format!("Failed to write secret store: {}", e));
+        }
+        Ok(())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_file(name: &str) -> EncryptedFile {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        EncryptedFile {
+            path: dir.join(SECRETS_FILE),
+            // Keep tests fast; production files use KDF_ITERATIONS
+            iterations: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_encrypted_file_roundtrip() {
+        let file = test_file("khaos_keychain_roundtrip");
+        let mut session = Session::new("pw");
+        assert!(file.read(&mut session).unwrap().is_empty());
+
+        let mut secrets = BTreeMap::new();
+        secrets.insert(provider_key("openai"), "sk-test-123".to_string());
+        file.write(&mut session, &secrets).unwrap();
+
+        assert!(file.exists());
+        assert_eq!(file.read(&mut Session::new("pw")).unwrap(), secrets);
+
+        // The key never appears in plain text on disk
+        let on_disk = fs::read_to_string(&file.path).unwrap();
+        assert!(!on_disk.contains("sk-test-123"));
+        assert!(!on_disk.contains("openai"));
+
+        let _ = fs::remove_dir_all(file.path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_encrypted_file_rejects_wrong_passphrase() {
+        let file = test_file("khaos_keychain_wrong_pass");
+        file.write(&mut Session::new("right"), &BTreeMap::new())
+            .unwrap();
+
+        assert_eq!(
+            file.read(&mut Session::new("wrong")).unwrap_err(),
+            "Incorrect passphrase or corrupted secret store"
+        );
+
+        let _ = fs::remove_dir_all(file.path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_session_reuses_derived_key() {
+        let file = test_file("khaos_keychain_session");
+        let mut session = Session::new("pw");
+        file.write(&mut session, &BTreeMap::new()).unwrap();
+        let salt = session.derived.as_ref().unwrap().salt.clone();
+
+        // Later writes keep the salt, so the cached key stays valid
+        let mut secrets = BTreeMap::new();
+        secrets.insert(provider_key("groq"), "gsk-1".to_string());
+        file.write(&mut session, &secrets).unwrap();
+        assert_eq!(session.derived.as_ref().unwrap().salt, salt);
+
+        // Reads use the cached key instead of deriving it again
+        session.derived.as_mut().unwrap().key = [0u8; 32];
+        assert!(file.read(&mut session).is_err());
+
+        let _ = fs::remove_dir_all(file.path.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encrypted_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = test_file("khaos_keychain_mode");
+        file.write(&mut Session::new("pw"), &BTreeMap::new())
+            .unwrap();
+
+        let mode = fs::metadata(&file.path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let _ = fs::remove_dir_all(file.path.parent().unwrap());
+    }
+}
@@ -0,0 +1,181 @@
+// Manifest Service
+// Reads and edits a project's manifest.json, keeping fields this app does not
+// model intact
+
+use crate::services::{config, discovery, import};
+use crate::types::ProjectManifest;
+use std::fs;
+use std::path::Path;
+
+/// Read a project's manifest (empty if the project has no manifest.json yet)
+pub fn read_manifest(project_path: &Path) -> Result<ProjectManifest, String> {
+    if !discovery::is_kspd(project_path) {
+        return Err(format!("Not a KSPD project: {}", project_path.display()));
+    }
+
+    match fs::read_to_string(project_path.join("manifest.json")) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse manifest.json: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ProjectManifest::default()),
+        Err(e) => Err(format!("Failed to read manifest.json: {}", e)),
+    }
+}
+
+/// Replace the modelled manifest fields with those in `update` (a None field
+/// is removed) and merge in its extra fields. Fields on disk that the update
+/// does not mention are preserved. Returns the manifest as written.
+pub fn update_manifest(
+    project_path: &Path,
+    update: &ProjectManifest,
+) -> Result<ProjectManifest, String> {
+    let title = update.title.as_deref().unwrap_or("");
+    import::validate_title(title)?;
+
+    // Merge at the JSON level so unknown fields keep their original form
+    let current = read_manifest(project_path)?;
+    let mut merged = match serde_json::to_value(&current) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    for field in ProjectManifest::FIELDS {
+        merged.remove(*field);
+    }
+
+    // Extra fields first: a value kept there because it had an unexpected
+    // type is replaced when the update sets the typed field
+    let mut update = update.clone();
+    update.title = Some(title.trim().to_string());
+    merged.extend(std::mem::take(&mut update.extra));
+    if let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(&update) {
+        merged.extend(fields);
+    }
+
+    let json = serde_json::to_string_pretty(&merged)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    config::write_atomic(&project_path.join("manifest.json"), json.as_bytes())?;
+
+    tracing::info!("Updated manifest for {}", project_path.display());
+    serde_json::from_value(serde_json::Value::Object(merged))
+        .map_err(|e| format!("Failed to parse manifest.json: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ManifestContact;
+    use std::path::PathBuf;
+
+    fn setup_project(name: &str, manifest: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name).join("film.kspd");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("manifest.json"), manifest).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_read_manifest_keeps_unknown_fields() {
+        let dir = setup_project(
+            "khaos_manifest_read",
+            r#"{"title": "Film", "draft_number": 3, "version": "1.0.0"}"#,
+        );
+
+        let manifest = read_manifest(&dir).unwrap();
+        assert_eq!(manifest.title.as_deref(), Some("Film"));
+        assert_eq!(manifest.draft_number, Some(3));
+        assert_eq!(manifest.extra["version"], "1.0.0");
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_update_manifest_preserves_unknown_fields() {
+        let dir = setup_project(
+            "khaos_manifest_update",
+            r#"{"title": "Old", "author": "A", "genre": "Noir", "created": "2025-01-15"}"#,
+        );
+
+        let update = ProjectManifest {
+            title: Some("  New Title ".to_string()),
+            author: Some("B".to_string()),
+            logline: Some("A heist goes wrong.".to_string()),
+            contact: Some(ManifestContact {
+                email: Some("b@example.com".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let written = update_manifest(&dir, &update).unwrap();
+
+        assert_eq!(written.title.as_deref(), Some("New Title"));
+        assert_eq!(written.genre, None);
+
+        let on_disk: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(on_disk["title"], "New Title");
+        assert_eq!(on_disk["created"], "2025-01-15");
+        assert_eq!(on_disk["contact"]["email"], "b@example.com");
+        assert!(on_disk.get("genre").is_none());
+        assert!(on_disk["contact"].get("phone").is_none());
+
+        // No temporary files left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_mistyped_fields_do_not_hide_the_manifest() {
+        let dir = setup_project(
+            "khaos_manifest_mistyped",
+            r#"{"title": "Film", "author": "A", "draft_number": "2a",
+                "contact": "agent@example.com", "genre": ["noir", "heist"]}"#,
+        );
+
+        let manifest = read_manifest(&dir).unwrap();
+        assert_eq!(manifest.title.as_deref(), Some("Film"));
+        assert_eq!(manifest.author.as_deref(), Some("A"));
+        assert_eq!(manifest.draft_number, None);
+        assert_eq!(manifest.extra["draft_number"], "2a");
+        assert_eq!(manifest.extra["contact"], "agent@example.com");
+
+        // Untouched odd values survive an edit; a typed value replaces them
+        let update = ProjectManifest {
+            draft_number: Some(3),
+            ..manifest
+        };
+        update_manifest(&dir, &update).unwrap();
+        let on_disk: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(on_disk["draft_number"], 3);
+        assert_eq!(on_disk["genre"], serde_json::json!(["noir", "heist"]));
+        assert_eq!(on_disk["contact"], "agent@example.com");
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_update_manifest_validates_title() {
+        let dir = setup_project("khaos_manifest_invalid", r#"{"title": "Film"}"#);
+
+        let update = ProjectManifest {
+            title: Some("   ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            update_manifest(&dir, &update).unwrap_err(),
+            "Title cannot be empty"
+        );
+        assert_eq!(read_manifest(&dir).unwrap().title.as_deref(), Some("Film"));
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_read_manifest_rejects_non_project() {
+        let dir = std::env::temp_dir().join("khaos_manifest_not_project");
+        let _ = fs::create_dir_all(&dir);
+        assert!(read_manifest(&dir).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
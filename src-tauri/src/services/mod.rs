@@ -1,10 +1,27 @@
 // Services Layer
 // Core business logic and integrations (not yet implemented)
 
+pub mod analysis_runs;
+pub mod breakdown;
+pub mod bundle;
+pub mod compare;
 pub mod config;
 pub mod dashboard;
-pub mod keychain;
 pub mod discovery;
 pub mod export;
+pub mod fountain;
 pub mod import;
+pub mod keychain;
+pub mod manifest;
+pub mod ollama;
+pub mod project_index;
+pub mod project_state;
+pub mod provider_client;
+pub mod provider_models;
+pub mod report;
+pub mod search;
 pub mod settings;
+pub mod templates;
+#[cfg(test)]
+pub mod test_server;
+pub mod watcher;
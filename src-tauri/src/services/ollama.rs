@@ -0,0 +1,341 @@
+// Ollama Service
+// Detects the Ollama server and lists or pulls models through its HTTP API,
+// so a server running as a service, in a container or on another host works
+
+use crate::events::OllamaPullProgressEvent;
+use crate::types::{OllamaModel, OllamaStatus};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::time::Duration;
+
+pub const DEFAULT_HOST: &str = "http://localhost:11434";
+
+/// Timeout for status and listing requests (pulls run until done)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagModel>,
+}
+
+#[derive(Deserialize)]
+struct TagModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    modified_at: Option<String>,
+    #[serde(default)]
+    details: TagDetails,
+}
+
+#[derive(Default, Deserialize)]
+struct TagDetails {
+    #[serde(default)]
+    family: Option<String>,
+    #[serde(default)]
+    parameter_size: Option<String>,
+    #[serde(default)]
+    quantization_level: Option<String>,
+}
+
+/// One line of the NDJSON stream returned by /api/pull
+#[derive(Deserialize)]
+struct PullLine {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    digest: Option<String>,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Base URL of the Ollama server: the configured host, else OLLAMA_HOST, else
+/// localhost:11434. A host without a scheme is reached over http.
+pub fn resolve_host(configured: Option<&str>) -> String {
+    let host = configured
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            std::env::var("OLLAMA_HOST")
+                .ok()
+                .filter(|h| !h.trim().is_empty())
+        })
+        .unwrap_or_else(|| DEFAULT_HOST.to_string());
+    normalize_host(&host)
+}
+
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('/');
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.to_string()
+    } else {
+        format!("http://{}", host)
+    }
+}
+
+fn client(timeout: Option<Duration>) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder().connect_timeout(REQUEST_TIMEOUT);
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn get_text(host: &str, path: &str) -> Result<String, String> {
+    client(Some(REQUEST_TIMEOUT))?
+        .get(format!("{}{}", host, path))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {}: {}", host, e))?
+        .error_for_status()
+        .map_err(|e| format!("Ollama at {} returned an error: {}", host, e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read Ollama response: {}", e))
+}
+
+/// Server version, which doubles as the reachability check
+pub async fn get_version(host: &str) -> Result<String, String> {
+    let body = get_text(host, "/api/version").await?;
+    let version: VersionResponse = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse Ollama version: {}", e))?;
+    Ok(version.version)
+}
+
+/// Models installed on the server
+pub async fn list_models(host: &str) -> Result<Vec<OllamaModel>, String> {
+    parse_tags(&get_text(host, "/api/tags").await?)
+}
+
+pub(crate) fn parse_tags(body: &str) -> Result<Vec<OllamaModel>, String> {
+    let tags: TagsResponse = serde_json::from_str(body)
+        .map_err(|e| format!("Failed to parse Ollama model list: {}", e))?;
+    Ok(tags
+        .models
+        .into_iter()
+        .map(|m| OllamaModel {
+            name: m.name,
+            size: m.size,
+            family: m.details.family,
+            parameter_size: m.details.parameter_size,
+            quantization: m.details.quantization_level,
+            modified_at: m.modified_at,
+        })
+        .collect())
+}
+
+/// Whether an installed model name satisfies a requested model, where a
+/// request without a tag matches any tag ("llama3" matches "llama3:8b")
+pub fn model_matches(name: &str, model: &str) -> bool {
+    name == model
+        || name
+            .strip_prefix(model)
+            .is_some_and(|tag| tag.starts_with(':'))
+}
+
+/// Reachability, version and installed models, plus whether `model` is one of
+/// them
+pub async fn get_status(host: &str, model: &str) -> OllamaStatus {
+    let mut status = OllamaStatus {
+        host: host.to_string(),
+        reachable: false,
+        version: None,
+        model_available: false,
+        models: Vec::new(),
+        error: None,
+    };
+
+    match get_version(host).await {
+        Ok(version) => {
+            status.reachable = true;
+            status.version = Some(version);
+        }
+        Err(e) => {
+            status.error = Some(e);
+            return status;
+        }
+    }
+
+    match list_models(host).await {
+        Ok(models) => {
+            status.model_available = models.iter().any(|m| model_matches(&m.name, model));
+            if !status.model_available {
+                status.error = Some(format!(
+                    "Model '{}' not found. Pull it from Settings or run: ollama pull {}",
+                    model, model
+                ));
+            }
+            status.models = models;
+        }
+        Err(e) => status.error = Some(e),
+    }
+
+    status
+}
+
+/// Pull a model, calling `on_progress` for each status line the server
+/// streams. Returns once the server reports success.
+pub async fn pull_model(
+    host: &str,
+    model: &str,
+    mut on_progress: impl FnMut(OllamaPullProgressEvent),
+) -> Result<(), String> {
+    let response = client(None)?
+        .post(format!("{}/api/pull", host))
+        .json(&serde_json::json!({ "model": model, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {}: {}", host, e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to pull {}: {}", model, e))?;
+
+    let mut stream = response.bytes_stream();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut succeeded = false;
+
+    loop {
+        let finished = match stream.next().await {
+            Some(chunk) => {
+                let chunk = chunk.map_err(|e| format!("Failed to read pull progress: {}", e))?;
+                buffer.extend_from_slice(&chunk);
+                false
+            }
+            None => {
+                buffer.push(b'\n');
+                true
+            }
+        };
+
+        while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            if let Some(progress) = parse_pull_line(model, &line)? {
+                succeeded |= progress.done;
+                on_progress(progress);
+            }
+        }
+
+        if finished {
+            break;
+        }
+    }
+
+    if succeeded {
+        Ok(())
+    } else {
+        Err(format!("Pull of {} ended before completing", model))
+    }
+}
+
+fn parse_pull_line(model: &str, line: &[u8]) -> Result<Option<OllamaPullProgressEvent>, String> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let parsed: PullLine =
+        serde_json::from_str(line).map_err(|e| format!("Failed to parse pull progress: {}", e))?;
+    if let Some(error) = parsed.error {
+        return Err(format!("Failed to pull {}: {}", model, error));
+    }
+
+    Ok(Some(OllamaPullProgressEvent {
+        model: model.to_string(),
+        done: parsed.status == "success",
+        status: parsed.status,
+        digest: parsed.digest,
+        total: parsed.total,
+        completed: parsed.completed,
+        error: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_server::serve_once;
+
+    #[test]
+    fn test_resolve_host() {
+        assert_eq!(resolve_host(Some("gpu-box:11434/")), "http://gpu-box:11434");
+        assert_eq!(
+            resolve_host(Some(" https://ollama.internal ")),
+            "https://ollama.internal"
+        );
+        assert_eq!(normalize_host(DEFAULT_HOST), DEFAULT_HOST);
+    }
+
+    #[test]
+    fn test_parse_tags_and_model_matches() {
+        let body = r#"{"models":[
+            {"name":"llama3:8b","size":4661224676,"modified_at":"2024-05-01T10:00:00Z",
+             "details":{"family":"llama","parameter_size":"8.0B","quantization_level":"Q4_0"}},
+            {"name":"qwen3:latest","size":5200000000}
+        ]}"#;
+        let models = parse_tags(body).unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].size, 4661224676);
+        assert_eq!(models[0].quantization.as_deref(), Some("Q4_0"));
+        assert!(models[1].parameter_size.is_none());
+
+        assert!(model_matches("llama3:8b", "llama3"));
+        assert!(model_matches("llama3:8b", "llama3:8b"));
+        assert!(!model_matches("llama3.1:8b", "llama3"));
+        assert!(!model_matches("llama3:8b", "llama3:70b"));
+    }
+
+    #[test]
+    fn test_parse_pull_line_reports_errors() {
+        let progress = parse_pull_line(
+            "qwen3",
+            br#"{"status":"pulling abc","digest":"sha256:abc","total":100,"completed":40}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(progress.completed, Some(40));
+        assert!(!progress.done);
+
+        assert!(parse_pull_line("qwen3", b"  \n").unwrap().is_none());
+        let err = parse_pull_line(
+            "nope",
+            br#"{"error":"pull model manifest: file does not exist"}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("file does not exist"));
+    }
+
+    #[tokio::test]
+    async fn test_pull_model_streams_progress() {
+        let body = concat!(
+            "{\"status\":\"pulling manifest\"}\n",
+            "{\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":100,\"completed\":50}\n",
+            "{\"status\":\"pulling abc\",\"digest\":\"sha256:abc\",\"total\":100,\"completed\":100}\n",
+            "{\"status\":\"success\"}"
+        );
+        // The body is split mid-line to exercise buffering
+        let (host, _) = serve_once("200 OK", "application/x-ndjson", body).await;
+
+        let mut events = Vec::new();
+        pull_model(&host, "qwen3", |e| events.push(e))
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[1].completed, Some(50));
+        assert!(events[3].done);
+    }
+}
@@ -0,0 +1,251 @@
+// Project Index Service
+// On-disk cache of discovered project metadata keyed by project path and file
+// mtimes, so discovery only re-reads projects that changed since the last scan
+
+use crate::types::Project;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Bump when the cached Project shape or fingerprint inputs change
+const INDEX_VERSION: u32 = 2;
+
+/// Files and directories (relative to the project directory) whose mtimes
+/// and lengths make up a fingerprint. The empty path is the project directory
+/// itself. They cover what the project size is made of, so the cached size
+/// is refreshed along with the rest of the record.
+const FINGERPRINT_FILES: &[&str] = &[
+    "",
+    "manifest.json",
+    "metadata",
+    "metadata/scenes.json",
+    "metadata/characters.json",
+    "metadata/locations.json",
+    "metadata/analysis",
+    "metadata/artifacts",
+    "metadata/artifacts/story-storage.db",
+];
+
+/// Modification times and lengths of the files a Project record is derived from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct Fingerprint(Vec<Option<(u64, u64)>>);
+
+impl Fingerprint {
+    /// Stat the fingerprint files of a project (missing files are recorded as None)
+    pub fn of(project_path: &Path) -> Self {
+        Fingerprint(
+            FINGERPRINT_FILES
+                .iter()
+                .map(|rel| stat(&project_path.join(rel)))
+                .collect(),
+        )
+    }
+}
+
+/// Modification time in milliseconds and length
+fn stat(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime = metadata
+        .modified()
+        .ok()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_millis() as u64;
+    Some((mtime, metadata.len()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+struct IndexEntry {
+    fingerprint: Fingerprint,
+    project: Project,
+}
+
+/// Cached project records keyed by project path
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct ProjectIndex {
+    version: u32,
+    entries: HashMap<String, IndexEntry>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl ProjectIndex {
+    /// Return the cached project if its fingerprint is unchanged
+    pub fn get(&self, path: &str, fingerprint: &Fingerprint) -> Option<Project> {
+        self.entries
+            .get(path)
+            .filter(|entry| &entry.fingerprint == fingerprint)
+            .map(|entry| entry.project.clone())
+    }
+
+    pub fn insert(&mut self, path: String, fingerprint: Fingerprint, project: Project) {
+        self.entries.insert(
+            path,
+            IndexEntry {
+                fingerprint,
+                project,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Drop entries for projects that were not seen in the latest scan
+    pub fn retain_paths(&mut self, seen: &HashSet<String>) {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| seen.contains(path));
+        if self.entries.len() != before {
+            self.dirty = true;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Load the index from disk, starting empty if missing, unreadable or outdated
+    fn load(path: &Path) -> Self {
+        let index = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<ProjectIndex>(&content).ok())
+            .filter(|index| index.version == INDEX_VERSION);
+
+        match index {
+            Some(index) => {
+                tracing::debug!("Loaded project index with {} entries", index.len());
+                index
+            }
+            None => ProjectIndex {
+                version: INDEX_VERSION,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Write the index back to disk if anything changed since the last save
+    fn save_if_dirty(&mut self, path: &Path) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+
+        let json = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize project index: {}", e))?;
+        crate::services::config::write_atomic(path, json.as_bytes())?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Get the index file path ($XDG_CONFIG_HOME/khaos-ui/discovery-index.json)
+fn index_path() -> Result<PathBuf, String> {
+    Ok(crate::services::config::config_dir()?.join("discovery-index.json"))
+}
+
+static PROJECT_INDEX: OnceLock<Mutex<ProjectIndex>> = OnceLock::new();
+
+/// Run `f` against the process-wide index (loaded from disk on first use),
+/// then persist any changes it made.
+pub fn with_index<T>(f: impl FnOnce(&mut ProjectIndex) -> T) -> T {
+    let path = index_path().ok();
+    let lock = PROJECT_INDEX.get_or_init(|| {
+        Mutex::new(match &path {
+            Some(path) => ProjectIndex::load(path),
+            None => ProjectIndex::default(),
+        })
+    });
+    let mut index = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let result = f(&mut index);
+
+    if let Some(path) = &path {
+        if let Err(e) = index.save_if_dirty(path) {
+            tracing::warn!("{}", e);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(path: &str) -> Project {
+        Project {
+            id: Project::id_from_path(path),
+            title: "Cached".to_string(),
+            author: None,
+            path: path.to_string(),
+            scene_count: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_index_get_requires_matching_fingerprint() {
+        let mut index = ProjectIndex::default();
+        let fp = Fingerprint(vec![Some((1, 10)), None, Some((2, 20))]);
+        index.insert("/p/a.kspd".to_string(), fp.clone(), project("/p/a.kspd"));
+
+        assert!(index.get("/p/a.kspd", &fp).is_some());
+        // A different mtime or a different length both invalidate the entry
+        let touched = Fingerprint(vec![Some((1, 10)), Some((5, 0)), Some((2, 20))]);
+        assert!(index.get("/p/a.kspd", &touched).is_none());
+        let grown = Fingerprint(vec![Some((1, 10)), None, Some((2, 99))]);
+        assert!(index.get("/p/a.kspd", &grown).is_none());
+        assert!(index.get("/p/b.kspd", &fp).is_none());
+    }
+
+    #[test]
+    fn test_index_retain_paths() {
+        let mut index = ProjectIndex::default();
+        let fp = Fingerprint(vec![]);
+        index.insert("/p/a.kspd".to_string(), fp.clone(), project("/p/a.kspd"));
+        index.insert("/p/b.kspd".to_string(), fp, project("/p/b.kspd"));
+
+        let seen: HashSet<String> = ["/p/a.kspd".to_string()].into_iter().collect();
+        index.retain_paths(&seen);
+
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_index_save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join("khaos_project_index_test");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("discovery-index.json");
+
+        let mut index = ProjectIndex::load(&path);
+        let fp = Fingerprint(vec![Some((42, 7))]);
+        index.insert("/p/a.kspd".to_string(), fp.clone(), project("/p/a.kspd"));
+        index.save_if_dirty(&path).unwrap();
+
+        let loaded = ProjectIndex::load(&path);
+        assert_eq!(loaded.get("/p/a.kspd", &fp).unwrap().title, "Cached");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_index_load_discards_other_versions() {
+        let dir = std::env::temp_dir().join("khaos_project_index_version_test");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("discovery-index.json");
+        fs::write(&path, r#"{"version": 0, "entries": {}}"#).unwrap();
+
+        let index = ProjectIndex::load(&path);
+        assert_eq!(index.version, INDEX_VERSION);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,266 @@
+// Project State Service
+// User organization of projects (tags, favorites, pinned order, archive state),
+// persisted in the config file next to the recent projects list
+
+use crate::services::{config, discovery};
+use crate::types::{Project, ProjectUserState};
+use std::collections::{BTreeMap, HashMap};
+
+const STATE_KEY: &str = "project_state";
+const PINNED_KEY: &str = "pinned_projects";
+
+/// Organization state for all projects, keyed by project ID
+#[derive(Debug, Default, PartialEq)]
+pub struct ProjectStates {
+    projects: HashMap<String, ProjectUserState>,
+    /// Pinned project IDs in display order
+    pinned: Vec<String>,
+}
+
+impl ProjectStates {
+    /// Load from the config (missing or invalid entries are treated as empty)
+    pub fn load() -> Result<Self, String> {
+        Ok(Self::from_config(&config::load_config()?))
+    }
+
+    fn from_config(config: &serde_json::Map<String, serde_json::Value>) -> Self {
+        let projects = config
+            .get(STATE_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let pinned = config
+            .get(PINNED_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        ProjectStates { projects, pinned }
+    }
+
+    fn write_to(
+        &self,
+        config: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), String> {
+        // Sorted and without default entries, so the file stays small and stable
+        let projects: BTreeMap<&String, &ProjectUserState> = self
+            .projects
+            .iter()
+            .filter(|(_, state)| **state != ProjectUserState::default())
+            .collect();
+
+        let projects = serde_json::to_value(projects)
+            .map_err(|e| format!("Failed to serialize project state: {}", e))?;
+        config.insert(STATE_KEY.to_string(), projects);
+        config.insert(
+            PINNED_KEY.to_string(),
+            serde_json::Value::from(self.pinned.clone()),
+        );
+        Ok(())
+    }
+
+    fn entry(&mut self, project_id: &str) -> &mut ProjectUserState {
+        self.projects.entry(project_id.to_string()).or_default()
+    }
+
+    /// Replace a project's tags. Tags are trimmed and deduplicated
+    /// case-insensitively, keeping the first spelling.
+    pub fn set_tags(&mut self, project_id: &str, tags: Vec<String>) {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+        self.entry(project_id).tags = normalized;
+    }
+
+    pub fn set_favorite(&mut self, project_id: &str, favorite: bool) {
+        self.entry(project_id).favorite = favorite;
+    }
+
+    pub fn set_archived(&mut self, project_id: &str, archived: bool) {
+        self.entry(project_id).archived = archived;
+    }
+
+    /// Pin a project at the end of the pinned list, or unpin it
+    pub fn set_pinned(&mut self, project_id: &str, pinned: bool) {
+        let is_pinned = self.pinned.iter().any(|id| id == project_id);
+        if pinned && !is_pinned {
+            self.pinned.push(project_id.to_string());
+        } else if !pinned {
+            self.pinned.retain(|id| id != project_id);
+        }
+    }
+
+    /// Replace the pinned order. Duplicate IDs are kept once; projects left
+    /// out are unpinned.
+    pub fn reorder_pinned(&mut self, project_ids: Vec<String>) {
+        let mut order: Vec<String> = Vec::new();
+        for id in project_ids {
+            if !order.contains(&id) {
+                order.push(id);
+            }
+        }
+        self.pinned = order;
+    }
+
+    /// Forget all state for a project (e.g. after it was deleted)
+    pub fn remove(&mut self, project_id: &str) {
+        self.projects.remove(project_id);
+        self.pinned.retain(|id| id != project_id);
+    }
+
+    /// All tags in use, sorted case-insensitively
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.projects.values().flat_map(|s| s.tags.iter()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    /// Copy organization state onto discovered projects. Archived projects are
+    /// dropped unless `include_archived`; pinned projects move to the front in
+    /// pinned order, the rest keep their existing order.
+    pub fn apply(&self, projects: Vec<Project>, include_archived: bool) -> Vec<Project> {
+        let mut projects: Vec<Project> = projects
+            .into_iter()
+            .map(|mut project| {
+                if let Some(state) = self.projects.get(&project.id) {
+                    project.tags = state.tags.clone();
+                    project.favorite = state.favorite;
+                    project.archived = state.archived;
+                }
+                project.pinned = self.pinned.iter().position(|id| id == &project.id);
+                project
+            })
+            .filter(|project| include_archived || !project.archived)
+            .collect();
+
+        // Stable sort: unpinned projects keep their relative order
+        projects.sort_by_key(|project| project.pinned.unwrap_or(usize::MAX));
+        projects
+    }
+}
+
+/// Discovered projects as every listing shows them: with the saved organization
+/// state and last-opened times, archived projects dropped unless `include_archived`
+pub fn apply_user_state(
+    projects: Vec<Project>,
+    include_archived: bool,
+) -> Result<Vec<Project>, String> {
+    let mut projects = ProjectStates::load()?.apply(projects, include_archived);
+    let history = discovery::load_recent_projects()?;
+    discovery::apply_last_opened(&history, &mut projects);
+    Ok(projects)
+}
+
+/// Apply `f` to the project states and save the result, as one locked update
+/// of the config. `f` runs again if the file changed underneath the update.
+pub fn update(mut f: impl FnMut(&mut ProjectStates)) -> Result<(), String> {
+    config::update_config(|config| {
+        let mut states = ProjectStates::from_config(config);
+        f(&mut states);
+        states.write_to(config)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str, modified: i64) -> Project {
+        Project {
+            id: id.to_string(),
+            title: id.to_string(),
+            path: format!("/p/{}.kspd", id),
+            modified,
+            ..Default::default()
+        }
+    }
+
+    fn ids(projects: &[Project]) -> Vec<&str> {
+        projects.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_set_tags_normalizes() {
+        let mut states = ProjectStates::default();
+        states.set_tags(
+            "a",
+            vec![
+                " Drama ".into(),
+                "drama".into(),
+                "".into(),
+                "Draft 2".into(),
+            ],
+        );
+        assert_eq!(states.projects["a"].tags, vec!["Drama", "Draft 2"]);
+    }
+
+    #[test]
+    fn test_apply_hides_archived_and_orders_pinned_first() {
+        let mut states = ProjectStates::default();
+        states.set_archived("b", true);
+        states.set_favorite("c", true);
+        states.set_pinned("d", true);
+        states.set_pinned("c", true);
+
+        let projects = vec![
+            project("a", 4),
+            project("b", 3),
+            project("c", 2),
+            project("d", 1),
+        ];
+
+        let visible = states.apply(projects.clone(), false);
+        assert_eq!(ids(&visible), vec!["d", "c", "a"]);
+        assert_eq!(visible[0].pinned, Some(0));
+        assert!(visible[1].favorite);
+        assert_eq!(visible[2].pinned, None);
+
+        let all = states.apply(projects, true);
+        assert_eq!(ids(&all), vec!["d", "c", "a", "b"]);
+        assert!(all[3].archived);
+    }
+
+    #[test]
+    fn test_pinning_and_reorder() {
+        let mut states = ProjectStates::default();
+        states.set_pinned("a", true);
+        states.set_pinned("b", true);
+        states.set_pinned("a", true);
+        assert_eq!(states.pinned, vec!["a", "b"]);
+
+        states.reorder_pinned(vec!["b".into(), "a".into(), "b".into()]);
+        assert_eq!(states.pinned, vec!["b", "a"]);
+
+        states.set_pinned("b", false);
+        assert_eq!(states.pinned, vec!["a"]);
+    }
+
+    #[test]
+    fn test_config_roundtrip_preserves_other_keys() {
+        let mut config = serde_json::Map::new();
+        config.insert("recent_projects".into(), serde_json::json!(["a"]));
+
+        let mut states = ProjectStates::default();
+        states.set_tags("a", vec!["noir".into()]);
+        states.set_favorite("b", true);
+        states.set_favorite("b", false);
+        states.set_pinned("a", true);
+        states.write_to(&mut config).unwrap();
+
+        assert_eq!(config["recent_projects"], serde_json::json!(["a"]));
+        // Entries back at their defaults are not written
+        assert!(config[STATE_KEY].get("b").is_none());
+
+        let loaded = ProjectStates::from_config(&config);
+        assert_eq!(loaded.projects["a"].tags, vec!["noir"]);
+        assert_eq!(loaded.pinned, vec!["a"]);
+        assert_eq!(loaded.all_tags(), vec!["noir"]);
+    }
+}
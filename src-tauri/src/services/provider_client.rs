@@ -0,0 +1,497 @@
+// Provider Client Service
+// Minimal HTTP requests against provider APIs, used by the opt-in connection
+// test and model listing
+
+use crate::services::ollama;
+use crate::types::{ConnectionOutcome, ConnectionTest, ProviderInfo, ProviderModel};
+use std::time::{Duration, Instant};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Request and auth conventions of a provider's API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApiStyle {
+    /// Bearer token, GET /models/{model}
+    OpenAi,
+    /// Bearer token, GET /models and look for the model in the list. Custom
+    /// OpenAI-compatible servers often don't serve single-model lookups.
+    ModelList,
+    /// x-api-key header, GET /models/{model}
+    Anthropic,
+    /// No auth, GET /api/tags
+    Ollama,
+    Mock,
+}
+
+fn api_style(provider: &ProviderInfo) -> ApiStyle {
+    if provider.custom {
+        return ApiStyle::ModelList;
+    }
+    match provider.id.as_str() {
+        "anthropic" => ApiStyle::Anthropic,
+        "ollama" => ApiStyle::Ollama,
+        "mock" => ApiStyle::Mock,
+        _ => ApiStyle::OpenAi,
+    }
+}
+
+/// Public API base URL of a provider (custom providers use their own)
+pub fn default_base_url(provider: &ProviderInfo) -> Option<String> {
+    if let Some(ref base_url) = provider.base_url {
+        return Some(base_url.trim().trim_end_matches('/').to_string());
+    }
+    let base_url = match provider.id.as_str() {
+        "openai" => "https://api.openai.com/v1",
+        "groq" => "https://api.groq.com/openai/v1",
+        "mistralai" => "https://api.mistral.ai/v1",
+        "anthropic" => "https://api.anthropic.com/v1",
+        "ollama" => ollama::DEFAULT_HOST,
+        _ => return None,
+    };
+    Some(base_url.to_string())
+}
+
+fn authorize(
+    request: reqwest::RequestBuilder,
+    style: ApiStyle,
+    api_key: Option<&str>,
+) -> reqwest::RequestBuilder {
+    match (style, api_key) {
+        (ApiStyle::Anthropic, key) => request
+            .header("x-api-key", key.unwrap_or_default())
+            .header("anthropic-version", ANTHROPIC_VERSION),
+        (ApiStyle::OpenAi | ApiStyle::ModelList, Some(key)) => request.bearer_auth(key),
+        _ => request,
+    }
+}
+
+/// `{base_url}/models/{model}` with the model ID encoded as one path segment
+fn model_url(base_url: &str, model: &str) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(&format!("{}/models", base_url))
+        .map_err(|e| format!("Invalid base URL {}: {}", base_url, e))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Invalid base URL {}", base_url))?
+        .push(model);
+    Ok(url)
+}
+
+/// Outcome for a response status. A 404 means the model is unknown only when
+/// the error body says so; otherwise the URL itself is likely wrong.
+pub fn classify_status(status: u16, body: &str) -> ConnectionOutcome {
+    match status {
+        200..=299 => ConnectionOutcome::Ok,
+        401 | 403 => ConnectionOutcome::AuthFailed,
+        404 if mentions_model(body) => ConnectionOutcome::ModelNotFound,
+        429 => ConnectionOutcome::RateLimited,
+        _ => ConnectionOutcome::Error,
+    }
+}
+
+/// Whether a JSON error body's message or code is about the model
+fn mentions_model(body: &str) -> bool {
+    let code = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| Some(value.get("error")?.get("code")?.as_str()?.to_string()));
+    error_message(body)
+        .into_iter()
+        .chain(code)
+        .any(|text| text.to_lowercase().contains("model"))
+}
+
+/// Error text from a JSON error body ({"error": {"message": ...}} or
+/// {"error": "..."}), if any
+fn error_message(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let error = value.get("error")?;
+    error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .or_else(|| error.as_str())
+        .map(str::to_string)
+}
+
+fn outcome_message(outcome: ConnectionOutcome, model: &str) -> Option<String> {
+    match outcome {
+        ConnectionOutcome::Ok => None,
+        ConnectionOutcome::AuthFailed => Some("API key was rejected".to_string()),
+        ConnectionOutcome::ModelNotFound => Some(format!("Model '{}' not found", model)),
+        ConnectionOutcome::RateLimited => Some("Rate limited by the provider".to_string()),
+        ConnectionOutcome::Unreachable => Some("Provider is not reachable".to_string()),
+        ConnectionOutcome::Error => Some("Provider returned an error".to_string()),
+    }
+}
+
+/// Make one cheap authenticated request for `model` and report how it went.
+/// The API key is sent only to `base_url` and never logged.
+pub async fn test_connection(
+    provider: &ProviderInfo,
+    base_url: Option<&str>,
+    model: &str,
+    api_key: Option<&str>,
+) -> ConnectionTest {
+    let style = api_style(provider);
+    let mut result = ConnectionTest {
+        outcome: ConnectionOutcome::Ok,
+        latency_ms: None,
+        status_code: None,
+        base_url: base_url.map(str::to_string),
+        message: None,
+    };
+
+    if style == ApiStyle::Mock {
+        result.message = Some("Mock provider makes no requests".to_string());
+        return result;
+    }
+
+    let Some(base_url) = base_url else {
+        result.outcome = ConnectionOutcome::Error;
+        result.message = Some(format!("No base URL configured for {}", provider.title));
+        return result;
+    };
+
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            result.outcome = ConnectionOutcome::Error;
+            result.message = Some(format!("Failed to create HTTP client: {}", e));
+            return result;
+        }
+    };
+
+    let request = match style {
+        ApiStyle::Ollama => client.get(format!("{}/api/tags", base_url)),
+        ApiStyle::ModelList => {
+            authorize(client.get(format!("{}/models", base_url)), style, api_key)
+        }
+        _ => match model_url(base_url, model) {
+            Ok(url) => authorize(client.get(url), style, api_key),
+            Err(e) => {
+                result.outcome = ConnectionOutcome::Error;
+                result.message = Some(e);
+                return result;
+            }
+        },
+    };
+
+    let started = Instant::now();
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            result.outcome = ConnectionOutcome::Unreachable;
+            result.message = Some(format!("Failed to reach {}: {}", base_url, e));
+            return result;
+        }
+    };
+    result.latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    let status = response.status().as_u16();
+    result.status_code = Some(status);
+    let body = response.text().await.unwrap_or_default();
+    result.outcome = classify_status(status, &body);
+
+    if result.outcome == ConnectionOutcome::Ok {
+        let listed = match style {
+            ApiStyle::Ollama => ollama::parse_tags(&body)
+                .map(|models| models.iter().any(|m| ollama::model_matches(&m.name, model))),
+            ApiStyle::ModelList => {
+                parse_models(&body).map(|models| models.iter().any(|m| m.id == model))
+            }
+            _ => Ok(true),
+        };
+        match listed {
+            Ok(true) => {}
+            Ok(false) => result.outcome = ConnectionOutcome::ModelNotFound,
+            Err(e) => {
+                result.outcome = ConnectionOutcome::Error;
+                result.message = Some(e);
+            }
+        }
+    }
+
+    if result.message.is_none() {
+        result.message = match error_message(&body) {
+            Some(detail) if result.outcome != ConnectionOutcome::Ok => Some(format!(
+                "{}: {}",
+                outcome_message(result.outcome, model).unwrap_or_default(),
+                detail
+            )),
+            _ => outcome_message(result.outcome, model),
+        };
+    }
+
+    result
+}
+
+/// Models listed by the provider's models endpoint, or Ollama's installed
+/// models
+pub async fn list_models(
+    provider: &ProviderInfo,
+    base_url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<ProviderModel>, String> {
+    let style = api_style(provider);
+    match style {
+        ApiStyle::Mock => return Err("Mock provider has no models endpoint".to_string()),
+        ApiStyle::Ollama => {
+            return Ok(ollama::list_models(base_url)
+                .await?
+                .into_iter()
+                .map(|m| ProviderModel {
+                    id: m.name,
+                    display_name: None,
+                    context_window: None,
+                    capabilities: Vec::new(),
+                })
+                .collect())
+        }
+        _ => {}
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    // Anthropic pages its list (20 models by default)
+    let url = match style {
+        ApiStyle::Anthropic => format!("{}/models?limit=1000", base_url),
+        _ => format!("{}/models", base_url),
+    };
+
+    let response = authorize(client.get(url), style, api_key)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", base_url, e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read model list: {}", e))?;
+    if !status.is_success() {
+        return Err(format!(
+            "Failed to list models: {}",
+            error_message(&body).unwrap_or_else(|| status.to_string())
+        ));
+    }
+    parse_models(&body)
+}
+
+/// Parse a {"data": [...]} model list. Context length and capabilities are
+/// read from the fields Groq, Mistral and OpenRouter-style gateways add.
+fn parse_models(body: &str) -> Result<Vec<ProviderModel>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("Failed to parse model list: {}", e))?;
+    let data = value
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| "Failed to parse model list: missing data array".to_string())?;
+
+    let mut models: Vec<ProviderModel> = data
+        .iter()
+        .filter_map(|entry| {
+            let id = entry.get("id")?.as_str()?.to_string();
+            let display_name = ["display_name", "name"]
+                .iter()
+                .find_map(|key| entry.get(*key).and_then(|v| v.as_str()))
+                .filter(|name| *name != id)
+                .map(str::to_string);
+            let context_window = ["context_window", "max_context_length", "context_length"]
+                .iter()
+                .find_map(|key| entry.get(*key).and_then(|v| v.as_u64()))
+                .map(|n| n.min(u32::MAX as u64) as u32);
+            let capabilities = entry
+                .get("capabilities")
+                .and_then(|c| c.as_object())
+                .map(|caps| {
+                    caps.iter()
+                        .filter(|(_, enabled)| enabled.as_bool() == Some(true))
+                        .map(|(name, _)| match name.as_str() {
+                            "completion_chat" => "chat".to_string(),
+                            "function_calling" => "tools".to_string(),
+                            other => other.to_string(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(ProviderModel {
+                id,
+                display_name,
+                context_window,
+                capabilities,
+            })
+        })
+        .collect();
+
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_server::serve_once;
+
+    const JSON: &str = "application/json";
+
+    fn provider(id: &str) -> ProviderInfo {
+        ProviderInfo::find(id).unwrap()
+    }
+
+    #[test]
+    fn test_parse_models_reads_metadata() {
+        let body = r#"{"object":"list","data":[
+            {"id":"mistral-small-latest","max_context_length":32768,
+             "capabilities":{"completion_chat":true,"function_calling":true,"vision":false}},
+            {"id":"llama-3.3-70b-versatile","context_window":131072},
+            {"id":"claude-sonnet-4-20250514","display_name":"Claude Sonnet 4"}
+        ]}"#;
+        let models = parse_models(body).unwrap();
+
+        assert_eq!(models[0].id, "claude-sonnet-4-20250514");
+        assert_eq!(models[0].display_name.as_deref(), Some("Claude Sonnet 4"));
+        assert_eq!(models[1].context_window, Some(131072));
+        assert_eq!(models[2].capabilities, vec!["chat", "tools"]);
+        assert!(parse_models(r#"{"models":[]}"#).is_err());
+    }
+
+    #[test]
+    fn test_classify_status() {
+        let model_missing = r#"{"error":{"message":"The model 'gpt-5x' does not exist"}}"#;
+        assert_eq!(classify_status(200, ""), ConnectionOutcome::Ok);
+        assert_eq!(classify_status(401, ""), ConnectionOutcome::AuthFailed);
+        assert_eq!(classify_status(403, ""), ConnectionOutcome::AuthFailed);
+        assert_eq!(
+            classify_status(404, model_missing),
+            ConnectionOutcome::ModelNotFound
+        );
+        assert_eq!(
+            classify_status(404, r#"{"error":{"code":"model_not_found"}}"#),
+            ConnectionOutcome::ModelNotFound
+        );
+        // A wrong base URL is not a missing model
+        assert_eq!(
+            classify_status(404, "404 page not found"),
+            ConnectionOutcome::Error
+        );
+        assert_eq!(classify_status(429, ""), ConnectionOutcome::RateLimited);
+        assert_eq!(classify_status(500, ""), ConnectionOutcome::Error);
+    }
+
+    #[test]
+    fn test_model_url_encodes_the_model_id() {
+        let url = model_url("https://api.example.com/v1", "org/model v2?").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://api.example.com/v1/models/org%2Fmodel%20v2%3F"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_ok_sends_bearer_key() {
+        let (base_url, request) = serve_once("200 OK", JSON, r#"{"id":"gpt-4o"}"#).await;
+        let result = test_connection(
+            &provider("openai"),
+            Some(&base_url),
+            "gpt-4o",
+            Some("sk-test"),
+        )
+        .await;
+
+        assert_eq!(result.outcome, ConnectionOutcome::Ok);
+        assert!(result.latency_ms.is_some());
+        let request = request.await.unwrap().to_lowercase();
+        assert!(request.starts_with("get /models/gpt-4o "));
+        assert!(request.contains("authorization: bearer sk-test"));
+    }
+
+    #[tokio::test]
+    async fn test_connection_reports_failures_distinctly() {
+        let cases = [
+            (
+                "401 Unauthorized",
+                r#"{"error":{"message":"Invalid API key"}}"#,
+                ConnectionOutcome::AuthFailed,
+            ),
+            (
+                "404 Not Found",
+                r#"{"error":{"message":"no such model"}}"#,
+                ConnectionOutcome::ModelNotFound,
+            ),
+            (
+                "429 Too Many Requests",
+                "{}",
+                ConnectionOutcome::RateLimited,
+            ),
+        ];
+        for (status, body, expected) in cases {
+            let (base_url, _) = serve_once(status, JSON, body).await;
+            let result =
+                test_connection(&provider("anthropic"), Some(&base_url), "claude", Some("k")).await;
+            assert_eq!(result.outcome, expected);
+            assert!(result.message.is_some());
+        }
+
+        let (base_url, _) = serve_once(
+            "401 Unauthorized",
+            JSON,
+            r#"{"error":{"message":"Invalid API key"}}"#,
+        )
+        .await;
+        let result = test_connection(&provider("groq"), Some(&base_url), "llama", Some("k")).await;
+        assert_eq!(
+            result.message.as_deref(),
+            Some("API key was rejected: Invalid API key")
+        );
+        assert_eq!(result.status_code, Some(401));
+    }
+
+    #[tokio::test]
+    async fn test_connection_custom_provider_checks_model_list() {
+        let custom = crate::types::CustomProvider {
+            id: "lmstudio".to_string(),
+            title: "LM Studio".to_string(),
+            base_url: "http://localhost:1234/v1".to_string(),
+            api_key_env: None,
+            secret_ref: None,
+            default_model: "qwen2.5-7b-instruct".to_string(),
+            openai_compatible: true,
+        }
+        .to_provider_info();
+        let list = r#"{"data":[{"id":"qwen2.5-7b-instruct"}]}"#;
+
+        let (base_url, request) = serve_once("200 OK", JSON, list).await;
+        let result = test_connection(&custom, Some(&base_url), "qwen2.5-7b-instruct", None).await;
+        assert_eq!(result.outcome, ConnectionOutcome::Ok);
+        let request = request.await.unwrap().to_lowercase();
+        assert!(request.starts_with("get /models "));
+
+        let (base_url, _) = serve_once("200 OK", JSON, list).await;
+        let result = test_connection(&custom, Some(&base_url), "llama3", None).await;
+        assert_eq!(result.outcome, ConnectionOutcome::ModelNotFound);
+    }
+
+    #[tokio::test]
+    async fn test_connection_ollama_checks_model() {
+        let (base_url, _) = serve_once(
+            "200 OK",
+            JSON,
+            r#"{"models":[{"name":"qwen3:latest","size":1}]}"#,
+        )
+        .await;
+        let result = test_connection(&provider("ollama"), Some(&base_url), "llama3", None).await;
+        assert_eq!(result.outcome, ConnectionOutcome::ModelNotFound);
+
+        // Nothing listens on port 9 locally
+        let result = test_connection(
+            &provider("ollama"),
+            Some("http://127.0.0.1:9"),
+            "qwen3",
+            None,
+        )
+        .await;
+        assert_eq!(result.outcome, ConnectionOutcome::Unreachable);
+        assert!(result.latency_ms.is_none());
+    }
+}
@@ -0,0 +1,314 @@
+// Provider Models Service
+// Lists the models a provider offers, from its API, Ollama's installed models
+// or a static catalog when offline, cached for a few minutes
+
+use crate::services::provider_client;
+use crate::types::{ModelSource, ProviderInfo, ProviderModel, ProviderModelList};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+struct CatalogEntry {
+    provider: &'static str,
+    id: &'static str,
+    context_window: Option<u32>,
+    capabilities: &'static [&'static str],
+}
+
+const fn entry(
+    provider: &'static str,
+    id: &'static str,
+    context_window: Option<u32>,
+    capabilities: &'static [&'static str],
+) -> CatalogEntry {
+    CatalogEntry {
+        provider,
+        id,
+        context_window,
+        capabilities,
+    }
+}
+
+const CHAT_TOOLS: &[&str] = &["chat", "tools"];
+const CHAT_TOOLS_VISION: &[&str] = &["chat", "tools", "vision"];
+
+/// Known models for offline use and for filling in metadata the models
+/// endpoints leave out
+const CATALOG: &[CatalogEntry] = &[
+    entry(
+        "ollama",
+        "qwen3",
+        Some(40_960),
+        &["chat", "tools", "reasoning"],
+    ),
+    entry("ollama", "llama3.1", Some(131_072), CHAT_TOOLS),
+    entry("ollama", "llama3.2", Some(131_072), CHAT_TOOLS),
+    entry("ollama", "mistral", Some(32_768), CHAT_TOOLS),
+    entry("ollama", "gemma3", Some(131_072), &["chat", "vision"]),
+    entry("openai", "gpt-4o", Some(128_000), CHAT_TOOLS_VISION),
+    entry("openai", "gpt-4o-mini", Some(128_000), CHAT_TOOLS_VISION),
+    entry("openai", "gpt-4.1", Some(1_047_576), CHAT_TOOLS_VISION),
+    entry("openai", "gpt-4.1-mini", Some(1_047_576), CHAT_TOOLS_VISION),
+    entry(
+        "openai",
+        "o3-mini",
+        Some(200_000),
+        &["chat", "tools", "reasoning"],
+    ),
+    entry(
+        "mistralai",
+        "mistral-large-latest",
+        Some(131_072),
+        CHAT_TOOLS,
+    ),
+    entry(
+        "mistralai",
+        "mistral-small-latest",
+        Some(32_768),
+        CHAT_TOOLS,
+    ),
+    entry(
+        "mistralai",
+        "codestral-latest",
+        Some(256_000),
+        &["chat", "code"],
+    ),
+    entry(
+        "anthropic",
+        "claude-sonnet-4-20250514",
+        Some(200_000),
+        CHAT_TOOLS_VISION,
+    ),
+    entry(
+        "anthropic",
+        "claude-opus-4-20250514",
+        Some(200_000),
+        CHAT_TOOLS_VISION,
+    ),
+    entry(
+        "anthropic",
+        "claude-3-5-haiku-20241022",
+        Some(200_000),
+        CHAT_TOOLS,
+    ),
+    entry("groq", "llama-3.3-70b-versatile", Some(131_072), CHAT_TOOLS),
+    entry("groq", "llama-3.1-8b-instant", Some(131_072), CHAT_TOOLS),
+    entry("mock", "mock-model", None, &["chat"]),
+];
+
+fn catalog_entry(provider_id: &str, model_id: &str) -> Option<&'static CatalogEntry> {
+    // Ollama names carry a tag ("qwen3:8b"); the catalog lists the family
+    let base = if provider_id == "ollama" {
+        model_id.split(':').next().unwrap_or(model_id)
+    } else {
+        model_id
+    };
+    CATALOG
+        .iter()
+        .find(|e| e.provider == provider_id && (e.id == model_id || e.id == base))
+}
+
+/// Catalog models for a provider (empty for custom providers)
+pub fn catalog_models(provider_id: &str) -> Vec<ProviderModel> {
+    CATALOG
+        .iter()
+        .filter(|e| e.provider == provider_id)
+        .map(|e| ProviderModel {
+            id: e.id.to_string(),
+            display_name: None,
+            context_window: e.context_window,
+            capabilities: e.capabilities.iter().map(|c| c.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// Fill in context window and capabilities the API did not report
+fn enrich(provider_id: &str, models: &mut [ProviderModel]) {
+    for model in models.iter_mut() {
+        let Some(known) = catalog_entry(provider_id, &model.id) else {
+            continue;
+        };
+        if model.context_window.is_none() {
+            model.context_window = known.context_window;
+        }
+        if model.capabilities.is_empty() {
+            model.capabilities = known.capabilities.iter().map(|c| c.to_string()).collect();
+        }
+    }
+}
+
+fn cache() -> &'static Mutex<HashMap<String, (Instant, ProviderModelList)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, ProviderModelList)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached(key: &str, ttl: Duration) -> Option<ProviderModelList> {
+    let cache = cache().lock().ok()?;
+    cache
+        .get(key)
+        .filter(|(stored_at, _)| stored_at.elapsed() < ttl)
+        .map(|(_, list)| list.clone())
+}
+
+fn store(key: String, list: ProviderModelList) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(key, (Instant::now(), list));
+    }
+}
+
+/// Cache key for a provider's list: a changed base URL or API key (stored,
+/// cleared or from the environment) must not reuse the old list
+fn cache_key(provider_id: &str, base_url: Option<&str>, api_key: Option<&str>) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    api_key.hash(&mut hasher);
+    format!(
+        "{}|{}|{:016x}",
+        provider_id,
+        base_url.unwrap_or_default(),
+        hasher.finish()
+    )
+}
+
+/// Models for a provider. Lists fetched from the provider are cached per
+/// base URL and API key; `refresh` bypasses the cache. When the provider
+/// cannot be asked the catalog is returned with the reason in `error`.
+pub async fn list_models(
+    provider: &ProviderInfo,
+    base_url: Option<&str>,
+    api_key: Option<&str>,
+    refresh: bool,
+) -> ProviderModelList {
+    let key = cache_key(&provider.id, base_url, api_key);
+    if !refresh {
+        if let Some(list) = cached(&key, CACHE_TTL) {
+            return list;
+        }
+    }
+
+    let fetched_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    let fetched = if provider.id == "mock" {
+        Err(None)
+    } else if provider.requires_key && api_key.is_none() {
+        Err(Some(format!(
+            "No API key for {}; showing known models",
+            provider.title
+        )))
+    } else if let Some(base_url) = base_url {
+        provider_client::list_models(provider, base_url, api_key)
+            .await
+            .map_err(Some)
+    } else {
+        Err(Some(format!(
+            "No base URL configured for {}",
+            provider.title
+        )))
+    };
+
+    match fetched {
+        Ok(mut models) => {
+            enrich(&provider.id, &mut models);
+            let list = ProviderModelList {
+                provider: provider.id.clone(),
+                models,
+                source: if provider.id == "ollama" {
+                    ModelSource::Ollama
+                } else {
+                    ModelSource::Api
+                },
+                fetched_at,
+                error: None,
+            };
+            store(key, list.clone());
+            list
+        }
+        Err(error) => {
+            if let Some(ref e) = error {
+                tracing::debug!("Using model catalog for {}: {}", provider.id, e);
+            }
+            ProviderModelList {
+                provider: provider.id.clone(),
+                models: catalog_models(&provider.id),
+                source: ModelSource::Catalog,
+                fetched_at,
+                error,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str) -> ProviderModel {
+        ProviderModel {
+            id: id.to_string(),
+            display_name: None,
+            context_window: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_catalog_covers_builtin_defaults() {
+        for provider in ProviderInfo::all() {
+            assert!(
+                catalog_entry(&provider.id, &provider.default_model).is_some(),
+                "no catalog entry for {}",
+                provider.default_model
+            );
+        }
+        assert!(catalog_models("vllm").is_empty());
+    }
+
+    #[test]
+    fn test_enrich_fills_missing_metadata() {
+        let mut models = vec![model("qwen3:8b"), model("custom-finetune")];
+        models[0].context_window = Some(8192);
+        enrich("ollama", &mut models);
+
+        assert_eq!(models[0].context_window, Some(8192));
+        assert!(models[0].capabilities.contains(&"reasoning".to_string()));
+        assert!(models[1].capabilities.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_models_falls_back_to_catalog_and_caches() {
+        let openai = ProviderInfo::find("openai").unwrap();
+        let list = list_models(&openai, Some("http://127.0.0.1:9"), None, false).await;
+        assert_eq!(list.source, ModelSource::Catalog);
+        assert!(list.error.is_some());
+        assert!(list.models.iter().any(|m| m.id == "gpt-4o"));
+
+        store(
+            "test|cache".to_string(),
+            ProviderModelList {
+                models: vec![model("cached")],
+                source: ModelSource::Api,
+                error: None,
+                ..list
+            },
+        );
+        assert!(cached("test|cache", CACHE_TTL).is_some());
+        assert!(cached("test|cache", Duration::ZERO).is_none());
+        assert!(cached("test|missing", CACHE_TTL).is_none());
+    }
+
+    #[test]
+    fn test_cache_key_follows_api_key() {
+        let url = Some("https://api.openai.com/v1");
+        let old_key = cache_key("openai", url, Some("sk-old"));
+        assert_eq!(old_key, cache_key("openai", url, Some("sk-old")));
+        assert_ne!(old_key, cache_key("openai", url, Some("sk-new")));
+        assert_ne!(old_key, cache_key("openai", url, None));
+        assert!(!old_key.contains("sk-old"));
+    }
+}
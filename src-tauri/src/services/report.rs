@@ -0,0 +1,396 @@
+// Report Service
+// Renders analysis exports as a single self-contained HTML document (inline
+// CSS, no external assets) for sharing outside the app
+
+use crate::services::export::ExportData;
+
+const REPORT_CSS: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; color: #1f2328; max-width: 960px; margin: 0 auto; padding: 32px 24px; line-height: 1.5; }
+h1 { margin-bottom: 4px; }
+h2 { border-bottom: 2px solid #d0d7de; padding-bottom: 4px; margin-top: 48px; }
+h3 { margin: 0 0 8px; }
+.subtitle { color: #656d76; margin-top: 0; }
+nav { background: #f6f8fa; border: 1px solid #d0d7de; border-radius: 6px; padding: 12px 20px; }
+nav ol { margin: 4px 0; padding-left: 20px; }
+nav ol ol { font-size: 0.9em; }
+.counts { display: flex; gap: 12px; flex-wrap: wrap; }
+.count { border: 1px solid #d0d7de; border-radius: 6px; padding: 12px 16px; min-width: 140px; }
+.count strong { display: block; font-size: 1.6em; }
+.entry { border: 1px solid #d0d7de; border-radius: 6px; padding: 16px; margin: 16px 0; page-break-inside: avoid; }
+.meta { color: #656d76; font-size: 0.9em; margin: 0 0 8px; }
+.tag { display: inline-block; background: #ddf4ff; color: #0969da; border-radius: 12px; padding: 0 10px; margin-right: 6px; font-size: 0.85em; }
+.empty { color: #8c959f; font-style: italic; }
+dt { font-weight: 600; margin-top: 8px; }
+dd { margin-left: 0; }
+a { color: #0969da; text-decoration: none; }
+@media print { nav { page-break-after: always; } a { color: inherit; } }
+"#;
+
+/// Render the export as an HTML report. Sections follow the export scope;
+/// every section and entry has an anchor linked from the table of contents.
+pub fn export_html(data: &ExportData) -> Result<String, String> {
+    let mut toc = String::from("<li><a href=\"#summary\">Summary</a></li>\n");
+    let mut body = String::new();
+
+    body.push_str("<section id=\"summary\">\n<h2>Summary</h2>\n<div class=\"counts\">\n");
+    if let Some(scenes) = &data.scenes {
+        let analyzed = scenes.iter().filter(|s| s.analysis.is_some()).count();
+        push_count(&mut body, "Scenes", scenes.len(), analyzed);
+    }
+    if let Some(characters) = &data.characters {
+        let analyzed = characters.iter().filter(|c| c.analysis.is_some()).count();
+        push_count(&mut body, "Characters", characters.len(), analyzed);
+    }
+    if let Some(locations) = &data.locations {
+        let analyzed = locations.iter().filter(|l| l.analysis.is_some()).count();
+        push_count(&mut body, "Locations", locations.len(), analyzed);
+    }
+    body.push_str("</div>\n</section>\n");
+
+    if let Some(scenes) = &data.scenes {
+        let mut entries = Vec::new();
+        body.push_str("<section id=\"scenes\">\n<h2>Scenes</h2>\n");
+        for scene in scenes {
+            let s = &scene.summary;
+            let anchor = anchor("scene", &s.id);
+            let heading = format!("{}. {}", s.index, s.slugline);
+            entries.push((anchor.clone(), heading.clone()));
+
+            body.push_str(&format!(
+                "<article class=\"entry\" id=\"{}\">\n<h3>{}</h3>\n",
+                anchor,
+                escape(&heading)
+            ));
+            body.push_str(&format!(
+                "<p class=\"meta\">{} &middot; {} words &middot; {}</p>\n",
+                escape(&s.duration),
+                s.word_count,
+                escape(&s.characters.join(", "))
+            ));
+
+            match &scene.analysis {
+                Some(a) => {
+                    body.push_str("<dl>\n");
+                    push_field(&mut body, "Narrative role", &a.narrative_role);
+                    push_field(&mut body, "Emotional tone", &a.emotional_tone);
+                    push_field(&mut body, "Summary", &a.summary);
+                    push_field(&mut body, "Stakes", &a.stakes);
+                    push_list(&mut body, "Plot beats", &a.plot_beats);
+                    body.push_str("</dl>\n");
+                    push_tags(&mut body, &a.themes);
+                }
+                None => body.push_str(NOT_ANALYZED),
+            }
+            body.push_str("</article>\n");
+        }
+        body.push_str("</section>\n");
+        push_toc(&mut toc, "scenes", "Scenes", &entries);
+    }
+
+    if let Some(characters) = &data.characters {
+        let mut entries = Vec::new();
+        body.push_str("<section id=\"characters\">\n<h2>Characters</h2>\n");
+        for character in characters {
+            let c = &character.summary;
+            let anchor = anchor("character", &c.id);
+            entries.push((anchor.clone(), c.name.clone()));
+
+            body.push_str(&format!(
+                "<article class=\"entry\" id=\"{}\">\n<h3>{}</h3>\n",
+                anchor,
+                escape(&c.name)
+            ));
+            body.push_str(&format!(
+                "<p class=\"meta\">{} scenes &middot; {} lines &middot; {:.1}% of dialogue</p>\n",
+                c.scene_count, c.dialogue_lines, c.percentage
+            ));
+
+            match &character.analysis {
+                Some(a) => {
+                    body.push_str("<dl>\n");
+                    push_field(&mut body, "Narrative role", &a.narrative_role);
+                    push_field(&mut body, "Arc", &a.arc);
+                    push_field(&mut body, "Emotional journey", &a.emotional_journey);
+                    push_field(&mut body, "End state", &a.end_state);
+                    push_list(&mut body, "Key turning points", &a.key_turning_points);
+                    body.push_str("</dl>\n");
+                    push_tags(&mut body, &a.traits);
+                }
+                None => body.push_str(NOT_ANALYZED),
+            }
+            body.push_str("</article>\n");
+        }
+        body.push_str("</section>\n");
+        push_toc(&mut toc, "characters", "Characters", &entries);
+    }
+
+    if let Some(locations) = &data.locations {
+        let mut entries = Vec::new();
+        body.push_str("<section id=\"locations\">\n<h2>Locations</h2>\n");
+        for location in locations {
+            let l = &location.summary;
+            let anchor = anchor("location", &l.id);
+            entries.push((anchor.clone(), l.name.clone()));
+
+            body.push_str(&format!(
+                "<article class=\"entry\" id=\"{}\">\n<h3>{}</h3>\n",
+                anchor,
+                escape(&l.name)
+            ));
+            body.push_str(&format!(
+                "<p class=\"meta\">{} scenes &middot; {} pages</p>\n",
+                l.scene_count, l.page_count
+            ));
+
+            match &location.analysis {
+                Some(a) => {
+                    body.push_str("<dl>\n");
+                    push_field(&mut body, "Significance", &a.significance);
+                    push_field(&mut body, "Atmosphere", &a.atmosphere);
+                    push_field(&mut body, "Summary", &a.summary);
+                    body.push_str("</dl>\n");
+                    push_tags(&mut body, &a.themes);
+                }
+                None => body.push_str(NOT_ANALYZED),
+            }
+            body.push_str("</article>\n");
+        }
+        body.push_str("</section>\n");
+        push_toc(&mut toc, "locations", "Locations", &entries);
+    }
+
+    Ok(page(
+        &format!("{} \u{2014} Analysis Report", data.title),
+        &format!(
+            "<h1>{}</h1>\n<p class=\"subtitle\">Analysis report</p>\n<nav>\n<strong>Contents</strong>\n<ol>\n{}</ol>\n</nav>\n{}",
+            escape(&data.title),
+            toc,
+            body
+        ),
+    ))
+}
+
+const NOT_ANALYZED: &str = "<p class=\"empty\">Not analyzed</p>\n";
+
+/// Wrap body markup in a complete HTML document with the report styles
+pub fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        REPORT_CSS,
+        body
+    )
+}
+
+/// Escape text for use in HTML content and attribute values
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Element ID for an entity; characters outside [A-Za-z0-9_-] become '-'
+pub fn anchor(kind: &str, id: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}-{}", kind, id)
+}
+
+fn push_count(html: &mut String, label: &str, total: usize, analyzed: usize) {
+    html.push_str(&format!(
+        "<div class=\"count\"><strong>{}</strong>{} &middot; {} analyzed</div>\n",
+        total, label, analyzed
+    ));
+}
+
+fn push_toc(toc: &mut String, id: &str, label: &str, entries: &[(String, String)]) {
+    toc.push_str(&format!("<li><a href=\"#{}\">{}</a>", id, label));
+    if !entries.is_empty() {
+        toc.push_str("\n<ol>\n");
+        for (anchor, title) in entries {
+            toc.push_str(&format!(
+                "<li><a href=\"#{}\">{}</a></li>\n",
+                anchor,
+                escape(title)
+            ));
+        }
+        toc.push_str("</ol>\n");
+    }
+    toc.push_str("</li>\n");
+}
+
+fn push_field(html: &mut String, label: &str, value: &Option<String>) {
+    if let Some(value) = value.as_deref().filter(|v| !v.trim().is_empty()) {
+        html.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", label, escape(value)));
+    }
+}
+
+fn push_list(html: &mut String, label: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    html.push_str(&format!("<dt>{}</dt><dd><ul>\n", label));
+    for item in items {
+        html.push_str(&format!("<li>{}</li>\n", escape(item)));
+    }
+    html.push_str("</ul></dd>\n");
+}
+
+fn push_tags(html: &mut String, tags: &[String]) {
+    if tags.is_empty() {
+        return;
+    }
+    html.push_str("<p>");
+    for tag in tags {
+        html.push_str(&format!("<span class=\"tag\">{}</span>", escape(tag)));
+    }
+    html.push_str("</p>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::export::ExportScope;
+    use crate::types::{
+        CharacterAnalysis, CharacterDetail, CharacterSummary, LocationAnalysis, LocationDetail,
+        LocationSummary, SceneAnalysis, SceneDetail, SceneSummary,
+    };
+
+    fn sample_data() -> ExportData {
+        ExportData {
+            title: "Cops & <Robbers>".to_string(),
+            project_path: "/p/heist.kspd".to_string(),
+            scope: ExportScope::All,
+            scenes: Some(vec![
+                SceneDetail {
+                    summary: SceneSummary {
+                        id: "scn_001".to_string(),
+                        index: 1,
+                        slugline: "INT. VAULT - NIGHT".to_string(),
+                        duration: "2:30".to_string(),
+                        word_count: 120,
+                        line_count: 14,
+                        characters: vec!["MAYA".to_string()],
+                    },
+                    analysis: Some(SceneAnalysis {
+                        narrative_role: Some("Climax".to_string()),
+                        emotional_tone: Some("Tense".to_string()),
+                        ..Default::default()
+                    }),
+                },
+                SceneDetail {
+                    summary: SceneSummary {
+                        id: "scn 002".to_string(),
+                        index: 2,
+                        slugline: "EXT. ROOF - DAWN".to_string(),
+                        duration: "0:45".to_string(),
+                        word_count: 30,
+                        line_count: 4,
+                        characters: Vec::new(),
+                    },
+                    analysis: None,
+                },
+            ]),
+            characters: Some(vec![CharacterDetail {
+                summary: CharacterSummary {
+                    id: "chr_001".to_string(),
+                    name: "MAYA".to_string(),
+                    dialogue_lines: 40,
+                    words: 512,
+                    scene_count: 12,
+                    percentage: 41.5,
+                },
+                analysis: Some(CharacterAnalysis {
+                    arc: Some("From loner to leader".to_string()),
+                    key_turning_points: vec!["Betrayed by Leo".to_string()],
+                    ..Default::default()
+                }),
+            }]),
+            locations: Some(vec![LocationDetail {
+                summary: LocationSummary {
+                    id: "loc_001".to_string(),
+                    name: "VAULT".to_string(),
+                    scene_count: 3,
+                    page_count: 5,
+                },
+                analysis: Some(LocationAnalysis {
+                    significance: Some("Where it all ends".to_string()),
+                    ..Default::default()
+                }),
+            }]),
+        }
+    }
+
+    #[test]
+    fn test_export_html_is_self_contained() {
+        let html = export_html(&sample_data()).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("src="));
+        assert!(html.contains("<h1>Cops &amp; &lt;Robbers&gt;</h1>"));
+    }
+
+    #[test]
+    fn test_export_html_toc_links_to_anchors() {
+        let html = export_html(&sample_data()).unwrap();
+
+        for anchor in [
+            "summary",
+            "scenes",
+            "scene-scn_001",
+            "scene-scn-002",
+            "characters",
+            "character-chr_001",
+            "locations",
+            "location-loc_001",
+        ] {
+            assert!(
+                html.contains(&format!("href=\"#{}\"", anchor)),
+                "{}",
+                anchor
+            );
+            assert!(html.contains(&format!("id=\"{}\"", anchor)), "{}", anchor);
+        }
+    }
+
+    #[test]
+    fn test_export_html_content() {
+        let html = export_html(&sample_data()).unwrap();
+
+        assert!(html.contains("<strong>2</strong>Scenes &middot; 1 analyzed"));
+        assert!(html.contains("<dt>Narrative role</dt><dd>Climax</dd>"));
+        assert!(html.contains("<dt>Emotional tone</dt><dd>Tense</dd>"));
+        assert!(html.contains("<p class=\"empty\">Not analyzed</p>"));
+        assert!(html.contains("41.5% of dialogue"));
+        assert!(html.contains("<li>Betrayed by Leo</li>"));
+        assert!(html.contains("<dt>Significance</dt><dd>Where it all ends</dd>"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+}
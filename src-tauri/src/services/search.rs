@@ -0,0 +1,492 @@
+// Project Search Service
+// Fuzzy, ranked project search with structured filters, e.g.
+//   heist author:smith modified:>2026-01-01 scenes:>50 analyzed:partial tag:noir
+
+use crate::types::Project;
+use std::cmp::Ordering;
+
+/// Relative weight of a match in each searchable field
+const TITLE_WEIGHT: f64 = 3.0;
+const AUTHOR_WEIGHT: f64 = 2.0;
+const PATH_WEIGHT: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Split a leading comparison operator off a filter value (default: Equal)
+    fn parse(value: &str) -> (Self, &str) {
+        for (prefix, op) in [
+            (">=", Comparison::GreaterOrEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+            ("=", Comparison::Equal),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (op, rest);
+            }
+        }
+        (Comparison::Equal, value)
+    }
+
+    fn matches<T: PartialOrd>(self, actual: T, expected: T) -> bool {
+        match self {
+            Comparison::Less => actual < expected,
+            Comparison::LessOrEqual => actual <= expected,
+            Comparison::Equal => actual == expected,
+            Comparison::GreaterOrEqual => actual >= expected,
+            Comparison::Greater => actual > expected,
+        }
+    }
+}
+
+/// How much of a project has saved analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnalyzedState {
+    None,
+    Partial,
+    Full,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Author(String),
+    Title(String),
+    /// Days since the Unix epoch (UTC)
+    Modified(Comparison, i64),
+    Scenes(Comparison, usize),
+    Characters(Comparison, usize),
+    Locations(Comparison, usize),
+    Analyzed(AnalyzedState),
+    Ingested(bool),
+    Tag(String),
+    Favorite(bool),
+    Archived(bool),
+}
+
+/// A parsed search query: free-text terms plus structured filters
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    terms: Vec<String>,
+    filters: Vec<Filter>,
+}
+
+impl SearchQuery {
+    /// Parse a query string. Tokens of the form `key:value` with a known key
+    /// become filters; values may be quoted (`author:"Jane Smith"`).
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut parsed = SearchQuery::default();
+
+        for token in tokenize(query) {
+            let Some((key, value)) = token.split_once(':') else {
+                parsed.terms.push(token.to_lowercase());
+                continue;
+            };
+
+            let filter = match key.to_lowercase().as_str() {
+                "author" => Filter::Author(value.to_lowercase()),
+                "title" => Filter::Title(value.to_lowercase()),
+                "modified" => {
+                    let (op, date) = Comparison::parse(value);
+                    let day = parse_date(date)
+                        .ok_or_else(|| format!("Invalid date in \"{}\" (use YYYY-MM-DD)", token))?;
+                    Filter::Modified(op, day)
+                }
+                "scenes" => {
+                    let (op, n) = parse_count(&token, value)?;
+                    Filter::Scenes(op, n)
+                }
+                "characters" => {
+                    let (op, n) = parse_count(&token, value)?;
+                    Filter::Characters(op, n)
+                }
+                "locations" => {
+                    let (op, n) = parse_count(&token, value)?;
+                    Filter::Locations(op, n)
+                }
+                "analyzed" => Filter::Analyzed(match value.to_lowercase().as_str() {
+                    "none" | "no" => AnalyzedState::None,
+                    "partial" => AnalyzedState::Partial,
+                    "full" | "yes" => AnalyzedState::Full,
+                    _ => {
+                        return Err(format!(
+                            "Invalid value in \"{}\" (use none, partial or full)",
+                            token
+                        ))
+                    }
+                }),
+                "ingested" => Filter::Ingested(parse_flag(&token, value)?),
+                "tag" => Filter::Tag(value.to_lowercase()),
+                "favorite" => Filter::Favorite(parse_flag(&token, value)?),
+                "archived" => Filter::Archived(parse_flag(&token, value)?),
+                // Unknown keys are plain search text (e.g. "act:2")
+                _ => {
+                    parsed.terms.push(token.to_lowercase());
+                    continue;
+                }
+            };
+            parsed.filters.push(filter);
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.filters.is_empty()
+    }
+
+    /// Archived projects are only searched when the query asks about them
+    pub fn includes_archived(&self) -> bool {
+        self.filters
+            .iter()
+            .any(|filter| matches!(filter, Filter::Archived(_)))
+    }
+
+    fn matches_filters(&self, project: &Project) -> bool {
+        self.filters.iter().all(|filter| match filter {
+            Filter::Author(needle) => project
+                .author
+                .as_ref()
+                .map(|a| field_score(needle, a).is_some())
+                .unwrap_or(false),
+            Filter::Title(needle) => field_score(needle, &project.title).is_some(),
+            Filter::Modified(op, day) => op.matches(project.modified.div_euclid(86_400), *day),
+            Filter::Scenes(op, n) => op.matches(project.scene_count, *n),
+            Filter::Characters(op, n) => op.matches(project.character_count, *n),
+            Filter::Locations(op, n) => op.matches(project.location_count, *n),
+            Filter::Analyzed(state) => analyzed_state(project) == *state,
+            Filter::Ingested(ingested) => project.ingested == *ingested,
+            Filter::Tag(tag) => project.tags.iter().any(|t| t.to_lowercase() == *tag),
+            Filter::Favorite(favorite) => project.favorite == *favorite,
+            Filter::Archived(archived) => project.archived == *archived,
+        })
+    }
+
+    /// Relevance score for a project, or None if any term fails to match.
+    /// Every term must match the title, author or path.
+    fn score(&self, project: &Project) -> Option<f64> {
+        let mut total = 0.0;
+
+        for term in &self.terms {
+            let title = field_score(term, &project.title).map(|s| s * TITLE_WEIGHT);
+            let author = project
+                .author
+                .as_ref()
+                .and_then(|a| field_score(term, a))
+                .map(|s| s * AUTHOR_WEIGHT);
+            let path = field_score(term, &project.path).map(|s| s * PATH_WEIGHT);
+
+            total += [title, author, path]
+                .into_iter()
+                .flatten()
+                .reduce(f64::max)?;
+        }
+
+        Some(total)
+    }
+}
+
+/// Filter and rank projects. Results are ordered by relevance, then by
+/// modification time (newest first); an empty query keeps the input order.
+pub fn search_projects(projects: Vec<Project>, query: &SearchQuery) -> Vec<Project> {
+    let mut scored: Vec<(f64, Project)> = projects
+        .into_iter()
+        .filter(|p| query.matches_filters(p))
+        .filter_map(|p| query.score(&p).map(|score| (score, p)))
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .partial_cmp(a_score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.modified.cmp(&a.modified))
+    });
+
+    scored.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Split on whitespace, keeping double-quoted runs together and dropping the quotes
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+fn parse_count(token: &str, value: &str) -> Result<(Comparison, usize), String> {
+    let (op, number) = Comparison::parse(value);
+    let n = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid number in \"{}\"", token))?;
+    Ok((op, n))
+}
+
+fn parse_flag(token: &str, value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(format!("Invalid value in \"{}\" (use yes or no)", token)),
+    }
+}
+
+/// Parse YYYY-MM-DD into days since the Unix epoch
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Howard Hinnant's days_from_civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+fn analyzed_state(project: &Project) -> AnalyzedState {
+    let coverage = &project.analysis_coverage;
+    let present: Vec<f64> = [
+        (coverage.scenes, project.scene_count),
+        (coverage.characters, project.character_count),
+        (coverage.locations, project.location_count),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(percent, _)| percent)
+    .collect();
+
+    if present.iter().all(|p| *p <= 0.0) {
+        AnalyzedState::None
+    } else if present.iter().all(|p| *p >= 100.0) {
+        AnalyzedState::Full
+    } else {
+        AnalyzedState::Partial
+    }
+}
+
+/// Match quality of a lowercase term against a field, in (0, 1].
+/// Substring hits score highest; otherwise the closest word within the
+/// typo tolerance for the term's length scores by edit distance.
+fn field_score(term: &str, field: &str) -> Option<f64> {
+    let field = field.to_lowercase();
+
+    if field.contains(term) {
+        return Some(1.0);
+    }
+
+    let term_len = term.chars().count();
+    let max_typos = match term_len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    };
+    if max_typos == 0 {
+        return None;
+    }
+
+    field
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            // Allow a typo'd prefix of a longer word ("screnp" -> "screenplay")
+            let prefix: String = word.chars().take(term_len).collect();
+            edit_distance(term, word).min(edit_distance(term, &prefix))
+        })
+        .filter(|distance| *distance <= max_typos)
+        .min()
+        .map(|distance| 0.8 - 0.2 * distance as f64)
+}
+
+/// Edit distance counting insertions, deletions, substitutions and adjacent
+/// transpositions ("hiest" -> "heist") as one edit each
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AnalysisCoverage;
+
+    fn project(title: &str, author: Option<&str>, path: &str) -> Project {
+        Project {
+            id: Project::id_from_path(path),
+            title: title.to_string(),
+            author: author.map(|a| a.to_string()),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn titles(projects: &[Project]) -> Vec<&str> {
+        projects.iter().map(|p| p.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_query_terms_and_filters() {
+        let query = SearchQuery::parse(r#"heist author:"Jane Smith" scenes:>50 act:2"#).unwrap();
+        assert_eq!(query.terms, vec!["heist", "act:2"]);
+        assert_eq!(
+            query.filters,
+            vec![
+                Filter::Author("jane smith".to_string()),
+                Filter::Scenes(Comparison::Greater, 50)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_invalid_filters() {
+        assert!(SearchQuery::parse("scenes:>lots").is_err());
+        assert!(SearchQuery::parse("modified:>yesterday").is_err());
+        assert!(SearchQuery::parse("analyzed:maybe").is_err());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2026-01-01"), Some(20_454));
+        assert_eq!(parse_date("2026-13-01"), None);
+    }
+
+    #[test]
+    fn test_search_tolerates_typos() {
+        let projects = vec![
+            project("The Great Heist", None, "/p/heist.kspd"),
+            project("Morning Coffee", None, "/p/coffee.kspd"),
+        ];
+
+        let query = SearchQuery::parse("graet hiest").unwrap();
+        assert_eq!(
+            titles(&search_projects(projects, &query)),
+            vec!["The Great Heist"]
+        );
+    }
+
+    #[test]
+    fn test_search_ranks_title_above_path() {
+        let projects = vec![
+            project("Untitled", None, "/p/heist-drafts/untitled.kspd"),
+            project("Heist", None, "/p/h.kspd"),
+        ];
+
+        let query = SearchQuery::parse("heist").unwrap();
+        assert_eq!(
+            titles(&search_projects(projects, &query)),
+            vec!["Heist", "Untitled"]
+        );
+    }
+
+    #[test]
+    fn test_search_structured_filters() {
+        let mut a = project("Alpha", Some("Jane Smith"), "/p/a.kspd");
+        a.scene_count = 60;
+        a.modified = 20_500 * 86_400;
+        a.analysis_coverage = AnalysisCoverage {
+            scenes: 50.0,
+            ..Default::default()
+        };
+        let mut b = project("Beta", Some("John Doe"), "/p/b.kspd");
+        b.scene_count = 10;
+        b.modified = 20_000 * 86_400;
+
+        let run = |q: &str| {
+            let query = SearchQuery::parse(q).unwrap();
+            titles(&search_projects(vec![a.clone(), b.clone()], &query))
+                .into_iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run("author:smith"), vec!["Alpha"]);
+        assert_eq!(run("scenes:>50"), vec!["Alpha"]);
+        assert_eq!(run("modified:<2026-01-01"), vec!["Beta"]);
+        assert_eq!(run("analyzed:partial"), vec!["Alpha"]);
+        assert_eq!(run("analyzed:none"), vec!["Beta"]);
+    }
+
+    #[test]
+    fn test_search_organization_filters() {
+        let mut a = project("Alpha", None, "/p/a.kspd");
+        a.tags = vec!["Noir".to_string()];
+        a.favorite = true;
+        let mut b = project("Beta", None, "/p/b.kspd");
+        b.archived = true;
+
+        let run = |q: &str| {
+            let query = SearchQuery::parse(q).unwrap();
+            titles(&search_projects(vec![a.clone(), b.clone()], &query))
+                .into_iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run("tag:noir"), vec!["Alpha"]);
+        assert_eq!(run("favorite:yes"), vec!["Alpha"]);
+        assert_eq!(run("archived:yes"), vec!["Beta"]);
+        assert!(SearchQuery::parse("archived:no")
+            .unwrap()
+            .includes_archived());
+        assert!(!SearchQuery::parse("tag:noir").unwrap().includes_archived());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("hiest", "heist"), 1);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
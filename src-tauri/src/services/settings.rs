@@ -1,83 +1,287 @@
 // Settings Service Layer
-// Handles provider validation, Ollama detection, and API key checks.
+// Handles provider validation and API key checks.
 // Delegates config persistence to the config service.
 
-use crate::types::{OllamaStatus, ProviderInfo, ProviderValidationResult, SettingsConfig};
+use crate::events::SettingChange;
+use crate::services::{keychain, ollama, provider_client};
+use crate::types::{
+    ConnectionOutcome, ConnectionTest, CustomProvider, ProviderInfo, ProviderKeyStatus,
+    ProviderValidationResult, SettingsConfig,
+};
 
-/// Check if an API key environment variable is set for a provider.
-pub fn check_api_key(provider_id: &str) -> Result<bool, String> {
-    let provider = ProviderInfo::find(provider_id)
+/// Check if an API key is available for a built-in or custom provider, either
+/// from its environment variable or from the secret store.
+pub fn check_api_key(config: &SettingsConfig, provider_id: &str) -> Result<bool, String> {
+    let provider = config
+        .find_provider(provider_id)
         .ok_or_else(|| format!("Unknown provider: {}", provider_id))?;
 
     if !provider.requires_key {
         return Ok(true);
     }
 
+    if provider.custom {
+        return Ok(provider_api_key(&provider).is_some());
+    }
+
     match provider.env_var {
-        Some(ref var_name) => Ok(std::env::var(var_name).is_ok()),
+        Some(ref var_name) => Ok(std::env::var(var_name).is_ok() || has_stored_key(&provider)),
         None => Ok(true),
     }
 }
 
-/// Check if Ollama is installed by looking for the binary.
-pub fn check_ollama_installed() -> bool {
-    std::process::Command::new("which")
-        .arg("ollama")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+/// Secret store entry for a provider's key: its `secret_ref` if set,
+/// otherwise the provider's own entry
+pub fn secret_key(provider: &ProviderInfo) -> String {
+    provider
+        .secret_ref
+        .clone()
+        .unwrap_or_else(|| keychain::provider_key(&provider.id))
+}
+
+/// Whether a non-empty key is saved for the provider (false if the store is
+/// locked or unavailable)
+fn has_stored_key(provider: &ProviderInfo) -> bool {
+    matches!(
+        keychain::retrieve_secret(&secret_key(provider)),
+        Ok(Some(key)) if !key.is_empty()
+    )
+}
+
+/// The key requests to the provider use: its env var, else the stored key.
+/// A custom provider only uses its own key (see `custom_api_key`). The value
+/// is a secret and must never be logged.
+pub fn provider_api_key(provider: &ProviderInfo) -> Option<String> {
+    let stored = |provider: &ProviderInfo| keychain::retrieve_secret(&secret_key(provider));
+    if provider.custom {
+        return custom_api_key(provider, env_value, stored).ok().flatten();
+    }
+
+    provider.env_var.as_deref().and_then(env_value).or_else(|| {
+        stored(provider)
+            .ok()
+            .flatten()
+            .filter(|key| !key.is_empty())
+    })
 }
 
-/// Check if a specific model is available in Ollama.
-pub async fn check_ollama_model(model: &str) -> Result<bool, String> {
-    let output = tokio::process::Command::new("ollama")
-        .arg("list")
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run ollama list: {}", e))?;
+fn env_value(var_name: &str) -> Option<String> {
+    std::env::var(var_name)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
 
-    if !output.status.success() {
-        return Err("ollama list failed".to_string());
+/// A custom provider's own key: the stored secret when it names a
+/// `secret_ref`, else its `api_key_env`. Never an inherited variable that
+/// belongs to another provider (e.g. the user's real OPENAI_API_KEY), since
+/// the key is sent to the custom provider's host.
+fn custom_api_key(
+    provider: &ProviderInfo,
+    env_value: impl Fn(&str) -> Option<String>,
+    stored: impl Fn(&ProviderInfo) -> Result<Option<String>, String>,
+) -> Result<Option<String>, String> {
+    if provider.secret_ref.is_some() {
+        return stored(provider).map(|key| key.filter(|k| !k.is_empty()));
     }
+    Ok(provider.env_var.as_deref().and_then(env_value))
+}
+
+/// Variable the analysis tools read the provider's key from. Custom
+/// OpenAI-compatible providers are dispatched as "openai", so theirs is
+/// OPENAI_API_KEY whatever env var they name.
+fn child_env_var(provider: &ProviderInfo) -> Option<String> {
+    if provider.custom && provider.openai_compatible {
+        Some("OPENAI_API_KEY".to_string())
+    } else if provider.requires_key {
+        provider.env_var.clone()
+    } else {
+        None
+    }
+}
+
+/// Report where a provider's key would come from, reading the secret store
+pub fn provider_key_status(provider: &ProviderInfo) -> ProviderKeyStatus {
+    // A custom provider with a secret_ref ignores its env var
+    let env_set = provider
+        .env_var
+        .as_deref()
+        .filter(|_| !(provider.custom && provider.secret_ref.is_some()))
+        .map(|var_name| std::env::var(var_name).is_ok())
+        .unwrap_or(false);
+
+    let (stored, error) = match keychain::retrieve_secret(&secret_key(provider)) {
+        Ok(key) => (key.is_some_and(|k| !k.is_empty()), None),
+        Err(e) => (false, Some(e)),
+    };
+
+    ProviderKeyStatus {
+        provider: provider.id.clone(),
+        stored,
+        env_set,
+        error,
+    }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.lines().any(|line| {
-        let name = line.split_whitespace().next().unwrap_or("");
-        name == model || name.starts_with(&format!("{}:", model))
-    }))
+/// Credential variables for an analysis child process: `Some` sets the
+/// variable, `None` removes it from the inherited environment. The values are
+/// secrets; pass them only to the child (`apply_credentials`), never log them.
+pub type CredentialsEnv = Vec<(String, Option<String>)>;
+
+/// Credentials for the given providers, in priority order (the first provider
+/// using a variable decides it). A built-in provider whose variable is already
+/// set in the app's environment inherits it; otherwise its stored key is set.
+/// A custom provider's variable is always set from its own key, or removed
+/// when it has none, so another provider's inherited key never reaches it.
+pub fn provider_credentials_env(providers: &[ProviderInfo]) -> CredentialsEnv {
+    collect_credentials(providers, env_value, |provider| {
+        keychain::retrieve_secret(&secret_key(provider))
+    })
 }
 
-/// Get full Ollama status including installation and model availability.
-pub async fn get_ollama_status(model: &str) -> OllamaStatus {
-    let installed = check_ollama_installed();
+/// Stored credentials for every provider that uses an API key (for the daemon,
+/// which may serve any provider). The selected provider comes first so it
+/// decides a variable it shares with other providers.
+pub fn all_provider_credentials_env(config: &SettingsConfig) -> CredentialsEnv {
+    let mut providers = config.providers();
+    providers.sort_by_key(|p| p.id != config.provider);
+    provider_credentials_env(&providers)
+}
 
-    if !installed {
-        return OllamaStatus {
-            installed: false,
-            model_available: false,
-            error: Some("Ollama is not installed".to_string()),
+/// Apply credential variables to a child process command
+pub fn apply_credentials(command: &mut tokio::process::Command, credentials: CredentialsEnv) {
+    for (var_name, value) in credentials {
+        match value {
+            Some(value) => command.env(var_name, value),
+            None => command.env_remove(var_name),
         };
     }
+}
+
+fn collect_credentials(
+    providers: &[ProviderInfo],
+    env_value: impl Fn(&str) -> Option<String>,
+    stored: impl Fn(&ProviderInfo) -> Result<Option<String>, String>,
+) -> CredentialsEnv {
+    let mut env = CredentialsEnv::new();
+    let mut claimed: Vec<String> = Vec::new();
+
+    for provider in providers {
+        let Some(var_name) = child_env_var(provider) else {
+            continue;
+        };
+        if claimed.contains(&var_name) {
+            continue;
+        }
+        claimed.push(var_name.clone());
 
-    match check_ollama_model(model).await {
-        Ok(available) => OllamaStatus {
-            installed: true,
-            model_available: available,
-            error: if available {
+        if provider.custom {
+            let key = custom_api_key(provider, &env_value, &stored).unwrap_or_else(|e| {
+                tracing::warn!("No stored key available for {}: {}", provider.id, e);
                 None
-            } else {
-                Some(format!(
-                    "Model '{}' not found. Run: ollama pull {}",
-                    model, model
-                ))
-            },
-        },
-        Err(e) => OllamaStatus {
-            installed: true,
-            model_available: false,
-            error: Some(e),
-        },
+            });
+            env.push((var_name, key));
+            continue;
+        }
+
+        if env_value(&var_name).is_some() {
+            continue;
+        }
+        match stored(provider) {
+            Ok(Some(key)) if !key.is_empty() => env.push((var_name, Some(key))),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("No stored key available for {}: {}", provider.id, e),
+        }
+    }
+    env
+}
+
+fn is_valid_provider_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+fn is_valid_env_var(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Check custom provider definitions, returning one message per problem
+pub fn validate_custom_providers(providers: &[CustomProvider]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+
+    for provider in providers {
+        let id = provider.id.as_str();
+        if !is_valid_provider_id(id) {
+            errors.push(format!(
+                "Invalid provider ID '{}': use lowercase letters, digits, '-' or '_'",
+                id
+            ));
+        } else if ProviderInfo::find(id).is_some() {
+            errors.push(format!(
+                "Provider ID '{}' is already used by a built-in provider",
+                id
+            ));
+        } else if seen.contains(&id) {
+            errors.push(format!("Duplicate provider ID '{}'", id));
+        }
+        seen.push(id);
+
+        if provider.title.trim().is_empty() {
+            errors.push(format!("Provider '{}' needs a title", id));
+        }
+        let base_url = provider.base_url.trim();
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://"))
+            || base_url.ends_with("://")
+        {
+            errors.push(format!(
+                "Provider '{}' base URL must start with http:// or https://",
+                id
+            ));
+        }
+        if provider.default_model.trim().is_empty() {
+            errors.push(format!("Provider '{}' needs a default model", id));
+        }
+        if let Some(var_name) = provider.api_key_env.as_deref() {
+            if !is_valid_env_var(var_name) {
+                errors.push(format!(
+                    "Provider '{}' API key variable '{}' is not a valid environment variable name",
+                    id, var_name
+                ));
+            }
+        }
+        if provider
+            .secret_ref
+            .as_deref()
+            .is_some_and(|r| r.trim().is_empty())
+        {
+            errors.push(format!("Provider '{}' secret reference is empty", id));
+        }
+    }
+
+    errors
+}
+
+/// Range checks for the optional generation parameters
+fn check_generation_params(config: &SettingsConfig) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Some(temperature) = config.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            errors.push(format!(
+                "Temperature must be between 0 and 2 (got {})",
+                temperature
+            ));
+        }
     }
+    if config.max_tokens == Some(0) {
+        errors.push("Max tokens must be greater than 0".to_string());
+    }
+    errors
 }
 
 /// Validate a provider's configuration completeness.
@@ -87,7 +291,7 @@ pub async fn validate_provider(config: &SettingsConfig) -> ProviderValidationRes
     let mut errors = Vec::new();
 
     checks_run.push("provider_exists".to_string());
-    let provider = match ProviderInfo::find(provider_id) {
+    let provider = match config.find_provider(provider_id) {
         Some(p) => p,
         None => {
             errors.push(format!("Unknown provider: {}", provider_id));
@@ -95,18 +299,30 @@ pub async fn validate_provider(config: &SettingsConfig) -> ProviderValidationRes
                 valid: false,
                 checks_run,
                 errors,
+                connection: None,
             };
         }
     };
 
+    if provider.custom {
+        checks_run.push("custom_provider".to_string());
+        let definition: Vec<CustomProvider> = config
+            .custom_providers
+            .iter()
+            .filter(|p| p.id == provider.id)
+            .cloned()
+            .collect();
+        errors.extend(validate_custom_providers(&definition));
+    }
+
     if provider.requires_key {
         checks_run.push("api_key".to_string());
-        match check_api_key(provider_id) {
+        match check_api_key(config, provider_id) {
             Ok(true) => {}
             Ok(false) => {
                 let var_name = provider.env_var.as_deref().unwrap_or("UNKNOWN");
                 errors.push(format!(
-                    "Missing API key: {} environment variable not set",
+                    "Missing API key: {} environment variable not set and no key stored",
                     var_name
                 ));
             }
@@ -114,22 +330,23 @@ pub async fn validate_provider(config: &SettingsConfig) -> ProviderValidationRes
         }
     }
 
+    if config.temperature.is_some() || config.max_tokens.is_some() {
+        checks_run.push("generation_params".to_string());
+        errors.extend(check_generation_params(config));
+    }
+
     if provider_id == "ollama" {
-        checks_run.push("ollama_installed".to_string());
+        checks_run.push("ollama_reachable".to_string());
         checks_run.push("ollama_model".to_string());
 
+        let host = ollama::resolve_host(config.ollama_host.as_deref());
         let effective_model = config
-            .model
-            .as_deref()
-            .unwrap_or(&provider.default_model);
-        let status = get_ollama_status(effective_model).await;
-
-        if !status.installed {
-            errors.push("Ollama is not installed".to_string());
-        } else if !status.model_available {
-            if let Some(err) = status.error {
-                errors.push(err);
-            }
+            .effective_model()
+            .unwrap_or_else(|| provider.default_model.clone());
+        let status = ollama::get_status(&host, &effective_model).await;
+
+        if let Some(err) = status.error {
+            errors.push(err);
         }
     }
 
@@ -137,33 +354,307 @@ pub async fn validate_provider(config: &SettingsConfig) -> ProviderValidationRes
         valid: errors.is_empty(),
         checks_run,
         errors,
+        connection: None,
     }
 }
 
+/// API base URL for requests to a provider: `override_url` when given, the
+/// Ollama host from settings, or the provider's own endpoint
+pub fn provider_base_url(
+    config: &SettingsConfig,
+    provider: &ProviderInfo,
+    override_url: Option<&str>,
+) -> Option<String> {
+    match override_url.map(str::trim).filter(|url| !url.is_empty()) {
+        Some(url) if provider.id == "ollama" => Some(ollama::resolve_host(Some(url))),
+        Some(url) => Some(url.trim_end_matches('/').to_string()),
+        None if provider.id == "ollama" => {
+            Some(ollama::resolve_host(config.ollama_host.as_deref()))
+        }
+        None => provider_client::default_base_url(provider),
+    }
+}
+
+/// Opt-in live check: one minimal authenticated request for the configured
+/// model, against `base_url` when given (e.g. a local stand-in server)
+/// instead of the provider's own endpoint.
+pub async fn test_connection(
+    config: &SettingsConfig,
+    base_url: Option<&str>,
+) -> ProviderValidationResult {
+    let mut checks_run = vec!["provider_exists".to_string()];
+    let Some(provider) = config.find_provider(&config.provider) else {
+        return ProviderValidationResult {
+            valid: false,
+            checks_run,
+            errors: vec![format!("Unknown provider: {}", config.provider)],
+            connection: None,
+        };
+    };
+    checks_run.push("connection".to_string());
+
+    let model = config
+        .effective_model()
+        .unwrap_or_else(|| provider.default_model.clone());
+    let base_url = provider_base_url(config, &provider, base_url);
+
+    let api_key = provider_api_key(&provider);
+    let connection = if provider.requires_key && api_key.is_none() {
+        ConnectionTest {
+            outcome: ConnectionOutcome::AuthFailed,
+            latency_ms: None,
+            status_code: None,
+            base_url,
+            message: Some(format!(
+                "Missing API key: {} environment variable not set and no key stored",
+                provider.env_var.as_deref().unwrap_or("UNKNOWN")
+            )),
+        }
+    } else {
+        provider_client::test_connection(&provider, base_url.as_deref(), &model, api_key.as_deref())
+            .await
+    };
+
+    let errors = match connection.outcome {
+        ConnectionOutcome::Ok => Vec::new(),
+        _ => vec![connection
+            .message
+            .clone()
+            .unwrap_or_else(|| "Connection test failed".to_string())],
+    };
+
+    ProviderValidationResult {
+        valid: errors.is_empty(),
+        checks_run,
+        errors,
+        connection: Some(connection),
+    }
+}
+
+/// Fields that differ between two settings, compared by their JSON form and
+/// ordered by field name
+pub fn diff_settings(old: &SettingsConfig, new: &SettingsConfig) -> Vec<SettingChange> {
+    let as_map = |settings: &SettingsConfig| match serde_json::to_value(settings) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let (old, new) = (as_map(old), as_map(new));
+
+    let fields: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let before = old.get(field).cloned().unwrap_or_default();
+            let after = new.get(field).cloned().unwrap_or_default();
+            (before != after).then(|| SettingChange {
+                field: field.clone(),
+                old: before,
+                new: after,
+            })
+        })
+        .collect()
+}
+
+/// Settings the daemon reads only when it starts (its credential and
+/// OLLAMA_HOST environment)
+const DAEMON_START_FIELDS: &[&str] = &["custom_providers", "ollama_host", "provider"];
+
+/// Whether any of `changes` only takes effect once the daemon is restarted
+pub fn requires_daemon_restart(changes: &[SettingChange]) -> bool {
+    changes
+        .iter()
+        .any(|change| DAEMON_START_FIELDS.contains(&change.field.as_str()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_check_api_key_mock_provider() {
-        let result = check_api_key("mock");
+        let result = check_api_key(&SettingsConfig::default(), "mock");
         assert!(result.is_ok());
         assert!(result.unwrap());
     }
 
     #[test]
     fn test_check_api_key_ollama_provider() {
-        let result = check_api_key("ollama");
+        let result = check_api_key(&SettingsConfig::default(), "ollama");
         assert!(result.is_ok());
         assert!(result.unwrap());
     }
 
     #[test]
     fn test_check_api_key_unknown_provider() {
-        let result = check_api_key("nonexistent");
+        let result = check_api_key(&SettingsConfig::default(), "nonexistent");
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_collect_credentials_skips_set_and_missing() {
+        let providers = ProviderInfo::all();
+        let env = collect_credentials(
+            &providers,
+            |var_name| (var_name == "OPENAI_API_KEY").then(|| "sk-real".to_string()),
+            |provider| match provider.id.as_str() {
+                "openai" => Ok(Some("sk-openai".to_string())),
+                "anthropic" => Ok(Some("sk-ant".to_string())),
+                "groq" => Err("Secret store is locked".to_string()),
+                "mock" | "ollama" => panic!("keyless providers are not looked up"),
+                _ => Ok(None),
+            },
+        );
+
+        assert_eq!(
+            env,
+            vec![("ANTHROPIC_API_KEY".to_string(), Some("sk-ant".to_string()))]
+        );
+    }
+
+    fn custom(id: &str) -> CustomProvider {
+        CustomProvider {
+            id: id.to_string(),
+            title: "Local vLLM".to_string(),
+            base_url: "http://localhost:8000/v1".to_string(),
+            api_key_env: None,
+            secret_ref: Some("gateway-key".to_string()),
+            default_model: "llama3".to_string(),
+            openai_compatible: true,
+        }
+    }
+
+    #[test]
+    fn test_collect_credentials_first_provider_wins_shared_var() {
+        let config = SettingsConfig {
+            provider: "vllm".to_string(),
+            custom_providers: vec![custom("vllm")],
+            ..SettingsConfig::default()
+        };
+        let mut providers = config.providers();
+        providers.sort_by_key(|p| p.id != config.provider);
+
+        let env = collect_credentials(
+            &providers,
+            |_| None,
+            |provider| Ok(Some(format!("key-{}", secret_key(provider)))),
+        );
+
+        let openai: Vec<_> = env.iter().filter(|(k, _)| k == "OPENAI_API_KEY").collect();
+        assert_eq!(openai.len(), 1);
+        assert_eq!(openai[0].1.as_deref(), Some("key-gateway-key"));
+    }
+
+    #[test]
+    fn test_custom_provider_never_inherits_openai_key() {
+        let real_key =
+            |var_name: &str| (var_name == "OPENAI_API_KEY").then(|| "sk-real-openai".to_string());
+        let openai_key = |env: &CredentialsEnv| {
+            env.iter()
+                .find(|(k, _)| k == "OPENAI_API_KEY")
+                .map(|(_, v)| v.clone())
+        };
+
+        // Selected custom provider with a secret: its own key replaces the real one
+        let with_secret = custom("vllm").to_provider_info();
+        let env = collect_credentials(std::slice::from_ref(&with_secret), real_key, |_| {
+            Ok(Some("sk-gateway".to_string()))
+        });
+        assert_eq!(openai_key(&env), Some(Some("sk-gateway".to_string())));
+
+        // Secret missing from the store: the variable is removed, not inherited
+        let env = collect_credentials(&[with_secret], real_key, |_| Ok(None));
+        assert_eq!(openai_key(&env), Some(None));
+
+        // Keyless custom provider: removed as well
+        let mut keyless = custom("lmstudio");
+        keyless.secret_ref = None;
+        let env = collect_credentials(&[keyless.to_provider_info()], real_key, |_| {
+            panic!("keyless provider is not looked up")
+        });
+        assert_eq!(openai_key(&env), Some(None));
+
+        // Built-in OpenAI selected: the inherited key stays and customs don't touch it
+        let config = SettingsConfig {
+            provider: "openai".to_string(),
+            custom_providers: vec![custom("vllm")],
+            ..SettingsConfig::default()
+        };
+        let mut providers = config.providers();
+        providers.sort_by_key(|p| p.id != config.provider);
+        let env = collect_credentials(&providers, real_key, |_| Ok(Some("stored".to_string())));
+        assert_eq!(openai_key(&env), None);
+    }
+
+    #[test]
+    fn test_settings_providers_merges_custom() {
+        let mut clash = custom("openai");
+        clash.title = "Shadow".to_string();
+        let config = SettingsConfig {
+            custom_providers: vec![custom("vllm"), clash],
+            ..SettingsConfig::default()
+        };
+
+        let providers = config.providers();
+        assert_eq!(providers.len(), 7);
+        assert_eq!(config.find_provider("openai").unwrap().title, "OpenAI");
+
+        let vllm = config.find_provider("vllm").unwrap();
+        assert!(vllm.custom && vllm.requires_key);
+        assert_eq!(vllm.env_var.as_deref(), Some("OPENAI_API_KEY"));
+        assert_eq!(secret_key(&vllm), "gateway-key");
+    }
+
+    #[test]
+    fn test_validate_custom_providers() {
+        assert!(validate_custom_providers(&[custom("vllm")]).is_empty());
+
+        let bad = CustomProvider {
+            base_url: "localhost:8000".to_string(),
+            api_key_env: Some("my-key".to_string()),
+            default_model: " ".to_string(),
+            ..custom("Bad ID")
+        };
+        let errors =
+            validate_custom_providers(&[custom("vllm"), custom("vllm"), custom("groq"), bad]);
+        assert_eq!(errors.len(), 6);
+        assert!(errors[0].contains("Duplicate"));
+        assert!(errors[1].contains("built-in"));
+    }
+
+    #[test]
+    fn test_diff_settings_lists_changed_fields() {
+        let old = SettingsConfig::default();
+        assert!(diff_settings(&old, &old.clone()).is_empty());
+
+        let new = SettingsConfig {
+            provider: "groq".to_string(),
+            temperature: Some(0.2),
+            ..old.clone()
+        };
+        let changes = diff_settings(&old, &new);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, ["provider", "temperature"]);
+        assert_eq!(changes[0].old, "ollama");
+        assert_eq!(changes[0].new, "groq");
+        assert_eq!(changes[1].old, serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_requires_daemon_restart() {
+        let old = SettingsConfig::default();
+        let tuned = SettingsConfig {
+            temperature: Some(0.2),
+            ..old.clone()
+        };
+        assert!(!requires_daemon_restart(&diff_settings(&old, &tuned)));
+
+        let moved = SettingsConfig {
+            ollama_host: Some("http://gpu-box:11434".to_string()),
+            ..old.clone()
+        };
+        assert!(requires_daemon_restart(&diff_settings(&old, &moved)));
+    }
+
     #[test]
     fn test_provider_info_all_has_six_providers() {
         assert_eq!(ProviderInfo::all().len(), 6);
@@ -195,18 +686,41 @@ mod tests {
             provider: "mock".to_string(),
             model: None,
             projects_root: None,
+            ..SettingsConfig::default()
         };
         let result = validate_provider(&config).await;
         assert!(result.valid);
         assert!(result.errors.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_validate_provider_generation_params() {
+        let config = SettingsConfig {
+            provider: "mock".to_string(),
+            temperature: Some(2.5),
+            max_tokens: Some(0),
+            ..SettingsConfig::default()
+        };
+        let result = validate_provider(&config).await;
+        assert!(!result.valid);
+        assert!(result.checks_run.contains(&"generation_params".to_string()));
+        assert_eq!(result.errors.len(), 2);
+
+        let config = SettingsConfig {
+            temperature: Some(0.7),
+            max_tokens: Some(1024),
+            ..config
+        };
+        assert!(validate_provider(&config).await.valid);
+    }
+
     #[tokio::test]
     async fn test_validate_provider_unknown() {
         let config = SettingsConfig {
             provider: "nonexistent".to_string(),
             model: None,
             projects_root: None,
+            ..SettingsConfig::default()
         };
         let result = validate_provider(&config).await;
         assert!(!result.valid);
@@ -0,0 +1,438 @@
+// Project Templates Service
+// Scaffolds new, empty KSPD projects from built-in templates or user templates
+// stored in ~/.config/khaos-ui/templates/<id>/
+
+use crate::services::{discovery, import};
+use crate::types::{Project, ProjectTemplate};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Template used when none is specified
+pub const DEFAULT_TEMPLATE: &str = "blank";
+
+/// Optional descriptor inside a user template directory (not copied)
+const TEMPLATE_DESCRIPTOR: &str = "template.json";
+
+/// Entity files every KSPD starts with, each holding an empty array
+const METADATA_FILES: &[&str] = &["scenes.json", "characters.json", "locations.json"];
+
+struct BuiltinTemplate {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    /// Extra manifest fields
+    manifest: &'static [(&'static str, &'static str)],
+}
+
+const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        id: "blank",
+        title: "Blank Project",
+        description: "Empty project with no scenes, characters or locations",
+        manifest: &[],
+    },
+    BuiltinTemplate {
+        id: "feature",
+        title: "Feature Film",
+        description: "Empty feature-length screenplay",
+        manifest: &[("format", "feature")],
+    },
+    BuiltinTemplate {
+        id: "tv-episode",
+        title: "TV Episode",
+        description: "Empty episode of a television series",
+        manifest: &[("format", "tv_episode")],
+    },
+];
+
+enum TemplateSource {
+    Builtin(&'static BuiltinTemplate),
+    /// Directory whose contents are copied into the new project
+    User(PathBuf),
+}
+
+/// Fields the user enters for a new project
+#[derive(Debug, Default)]
+pub struct NewProject<'a> {
+    pub title: &'a str,
+    pub author: Option<&'a str>,
+    pub description: Option<&'a str>,
+    /// Template ID (defaults to DEFAULT_TEMPLATE)
+    pub template: Option<&'a str>,
+    /// Directory name without .kspd (e.g. a suggested name after a collision);
+    /// derived from the title when not given
+    pub name: Option<&'a str>,
+}
+
+/// Get the user templates directory ($XDG_CONFIG_HOME/khaos-ui/templates)
+fn templates_dir() -> Option<PathBuf> {
+    crate::services::config::config_dir()
+        .ok()
+        .map(|dir| dir.join("templates"))
+}
+
+/// List built-in templates followed by user templates.
+/// A user template with the same ID as a built-in one replaces it.
+pub fn list_templates() -> Vec<ProjectTemplate> {
+    list_templates_in(templates_dir().as_deref())
+}
+
+fn list_templates_in(dir: Option<&Path>) -> Vec<ProjectTemplate> {
+    let user = dir.map(read_user_templates).unwrap_or_default();
+
+    let mut templates: Vec<ProjectTemplate> = BUILTIN_TEMPLATES
+        .iter()
+        .filter(|b| !user.iter().any(|u| u.id == b.id))
+        .map(|b| ProjectTemplate {
+            id: b.id.to_string(),
+            title: b.title.to_string(),
+            description: Some(b.description.to_string()),
+            builtin: true,
+        })
+        .collect();
+    templates.extend(user);
+    templates
+}
+
+fn read_user_templates(dir: &Path) -> Vec<ProjectTemplate> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut templates: Vec<ProjectTemplate> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.to_string();
+            let descriptor: serde_json::Value =
+                fs::read_to_string(entry.path().join(TEMPLATE_DESCRIPTOR))
+                    .ok()
+                    .and_then(|content| serde_json::from_str(&content).ok())
+                    .unwrap_or_default();
+
+            Some(ProjectTemplate {
+                title: descriptor["title"].as_str().unwrap_or(&id).to_string(),
+                description: descriptor["description"].as_str().map(|d| d.to_string()),
+                id,
+                builtin: false,
+            })
+        })
+        .collect();
+
+    templates.sort_by(|a, b| a.id.cmp(&b.id));
+    templates
+}
+
+fn find_template(dir: Option<&Path>, id: &str) -> Result<TemplateSource, String> {
+    if let Some(path) = dir.filter(|_| is_template_name(id)).map(|d| d.join(id)) {
+        if path.is_dir() {
+            return Ok(TemplateSource::User(path));
+        }
+    }
+
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|b| b.id == id)
+        .map(TemplateSource::Builtin)
+        .ok_or_else(|| format!("Template not found: {}", id))
+}
+
+/// Whether `id` names a directory directly inside the templates directory: a
+/// single normal path component (not empty, `.`, `..` or a nested path)
+fn is_template_name(id: &str) -> bool {
+    let mut components = Path::new(id).components();
+    !id.contains(['/', '\\'])
+        && matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        )
+}
+
+/// Create a new project below `projects_dir`.
+/// Fails without touching the filesystem if the target already exists.
+pub fn create_project(projects_dir: &str, new: &NewProject) -> Result<Project, String> {
+    create_project_in(templates_dir().as_deref(), projects_dir, new)
+}
+
+fn create_project_in(
+    templates: Option<&Path>,
+    projects_dir: &str,
+    new: &NewProject,
+) -> Result<Project, String> {
+    import::validate_title(new.title)?;
+    let template = find_template(templates, new.template.unwrap_or(DEFAULT_TEMPLATE))?;
+
+    let target = import::resolve_target_path(projects_dir, new.name.unwrap_or(new.title))?;
+    if import::check_collision(&target) {
+        return Err(format!("Project already exists at {}", target));
+    }
+
+    fs::create_dir_all(projects_dir)
+        .map_err(|e| format!("Failed to create projects directory: {}", e))?;
+
+    // Claim the target first: create_dir fails if anything is already there,
+    // so two creations can't both pass an exists-check and race to the rename
+    let target_path = Path::new(&target);
+    fs::create_dir(target_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => format!("Target path already exists: {}", target),
+        _ => format!("Failed to create project directory: {}", e),
+    })?;
+
+    // Build in a hidden sibling and rename it over the empty claimed
+    // directory, so a failed scaffold never leaves a half-written project for
+    // discovery to pick up
+    let staging = target_path.with_file_name(format!(
+        ".{}.{}.tmp",
+        target_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("project"),
+        uuid::Uuid::new_v4()
+    ));
+
+    let result = scaffold(&staging, &template, new).and_then(|_| {
+        fs::rename(&staging, target_path).map_err(|e| format!("Failed to create project: {}", e))
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&staging);
+        let _ = fs::remove_dir(target_path);
+        return Err(e);
+    }
+
+    tracing::info!("Created project {} at {}", new.title, target);
+    discovery::read_project_metadata(target_path)
+}
+
+fn scaffold(dir: &Path, template: &TemplateSource, new: &NewProject) -> Result<(), String> {
+    fs::create_dir_all(dir.join("metadata"))
+        .map_err(|e| format!("Failed to create project directory: {}", e))?;
+
+    let mut manifest = serde_json::Map::new();
+    match template {
+        TemplateSource::Builtin(builtin) => {
+            for (key, value) in builtin.manifest {
+                manifest.insert(key.to_string(), serde_json::Value::from(*value));
+            }
+        }
+        TemplateSource::User(path) => {
+            copy_template_dir(path, dir)?;
+            if let Ok(content) = fs::read_to_string(dir.join("manifest.json")) {
+                manifest = serde_json::from_str(&content)
+                    .map_err(|e| format!("Invalid manifest.json in template: {}", e))?;
+            }
+        }
+    }
+
+    manifest.insert("title".to_string(), new.title.trim().into());
+    for (key, value) in [("author", new.author), ("description", new.description)] {
+        match value.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => manifest.insert(key.to_string(), value.into()),
+            None => manifest.remove(key),
+        };
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(dir.join("manifest.json"), json)
+        .map_err(|e| format!("Failed to write manifest.json: {}", e))?;
+
+    for file in METADATA_FILES {
+        let path = dir.join("metadata").join(file);
+        if !path.exists() {
+            fs::write(&path, "[]").map_err(|e| format!("Failed to write {}: {}", file, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a user template into the new project (symlinks and the descriptor are skipped)
+fn copy_template_dir(from: &Path, to: &Path) -> Result<(), String> {
+    let entries = fs::read_dir(from).map_err(|e| format!("Failed to read template: {}", e))?;
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if from.join(TEMPLATE_DESCRIPTOR) == entry.path() {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        let dest = to.join(&name);
+        if file_type.is_dir() {
+            fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+            copy_template_dir(&entry.path(), &dest)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &dest)
+                .map_err(|e| format!("Failed to copy template file: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read_json(path: &Path) -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_create_project_from_builtin_template() {
+        let root = temp_dir("khaos_templates_builtin");
+        let root_str = root.to_str().unwrap();
+
+        let new = NewProject {
+            title: "The Great Heist",
+            author: Some("Jane Smith"),
+            template: Some("feature"),
+            ..Default::default()
+        };
+        let project = create_project_in(None, root_str, &new).unwrap();
+
+        let path = root.join("The_Great_Heist.kspd");
+        assert_eq!(project.path, path.to_str().unwrap());
+        assert_eq!(project.title, "The Great Heist");
+        assert_eq!(project.author.as_deref(), Some("Jane Smith"));
+        assert_eq!(project.scene_count, 0);
+
+        let manifest = read_json(&path.join("manifest.json"));
+        assert_eq!(manifest["format"], "feature");
+        assert!(manifest.get("description").is_none());
+        for file in METADATA_FILES {
+            assert_eq!(
+                read_json(&path.join("metadata").join(file)),
+                serde_json::json!([])
+            );
+        }
+
+        // Second attempt collides
+        let err = create_project_in(None, root_str, &new).unwrap_err();
+        assert!(err.contains("already exists"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_create_project_from_user_template() {
+        let base = temp_dir("khaos_templates_user");
+        let templates = base.join("templates");
+        let tpl = templates.join("pilot");
+        fs::create_dir_all(tpl.join("notes")).unwrap();
+        fs::write(
+            tpl.join(TEMPLATE_DESCRIPTOR),
+            r#"{"title": "Pilot", "description": "Series pilot"}"#,
+        )
+        .unwrap();
+        fs::write(
+            tpl.join("manifest.json"),
+            r#"{"title": "Template", "genre": "Drama"}"#,
+        )
+        .unwrap();
+        fs::write(tpl.join("notes").join("outline.md"), "# Outline").unwrap();
+
+        let root = base.join("projects");
+        let new = NewProject {
+            title: "My Show",
+            description: Some("A show"),
+            template: Some("pilot"),
+            name: Some("My_Show_2"),
+            ..Default::default()
+        };
+        let project = create_project_in(Some(&templates), root.to_str().unwrap(), &new).unwrap();
+
+        let path = root.join("My_Show_2.kspd");
+        assert_eq!(project.description.as_deref(), Some("A show"));
+        let manifest = read_json(&path.join("manifest.json"));
+        assert_eq!(manifest["title"], "My Show");
+        assert_eq!(manifest["genre"], "Drama");
+        assert!(path.join("notes").join("outline.md").is_file());
+        assert!(!path.join(TEMPLATE_DESCRIPTOR).exists());
+        assert!(path.join("metadata").join("scenes.json").is_file());
+
+        let listed = list_templates_in(Some(&templates));
+        let pilot = listed.iter().find(|t| t.id == "pilot").unwrap();
+        assert_eq!(pilot.title, "Pilot");
+        assert!(!pilot.builtin);
+        assert_eq!(listed.len(), BUILTIN_TEMPLATES.len() + 1);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_create_project_rejects_invalid_input() {
+        let root = temp_dir("khaos_templates_invalid");
+        let root_str = root.to_str().unwrap();
+
+        let blank_title = NewProject {
+            title: "  ",
+            ..Default::default()
+        };
+        assert!(create_project_in(None, root_str, &blank_title).is_err());
+
+        let unknown = NewProject {
+            title: "Film",
+            template: Some("missing"),
+            ..Default::default()
+        };
+        assert_eq!(
+            create_project_in(None, root_str, &unknown).unwrap_err(),
+            "Template not found: missing"
+        );
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_find_template_rejects_non_names() {
+        let base = temp_dir("khaos_templates_names");
+        let templates = base.join("templates");
+        fs::create_dir_all(templates.join("nested/deeper")).unwrap();
+
+        for id in ["", ".", "..", "nested/deeper", "./nested", "/etc"] {
+            assert!(
+                find_template(Some(&templates), id).is_err(),
+                "{:?} should not resolve",
+                id
+            );
+        }
+        assert!(matches!(
+            find_template(Some(&templates), "nested"),
+            Ok(TemplateSource::User(_))
+        ));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_create_project_fails_on_existing_target() {
+        let root = temp_dir("khaos_templates_existing");
+        let root_str = root.to_str().unwrap();
+        // A file where the project would go is left alone
+        fs::write(root.join("Film.kspd"), "keep").unwrap();
+
+        let new = NewProject {
+            title: "Film",
+            ..Default::default()
+        };
+        assert_eq!(
+            create_project_in(None, root_str, &new).unwrap_err(),
+            format!("Target path already exists: {}/Film.kspd", root_str)
+        );
+        assert_eq!(fs::read_to_string(root.join("Film.kspd")).unwrap(), "keep");
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,64 @@
+// Test Server
+// One-shot local HTTP server standing in for provider and Ollama APIs in tests
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::task::JoinHandle;
+
+/// Serve one canned response on a local port, returning its base URL and a
+/// handle that yields the request (head and body). The body is sent in two
+/// writes so clients must cope with responses split across reads.
+pub async fn serve_once(
+    status: &'static str,
+    content_type: &'static str,
+    body: &'static str,
+) -> (String, JoinHandle<String>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            if n == 0 || request_complete(&request) {
+                break;
+            }
+        }
+
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        );
+        socket.write_all(head.as_bytes()).await.unwrap();
+        let (first, rest) = body.split_at(body.len() / 2);
+        socket.write_all(first.as_bytes()).await.unwrap();
+        socket.flush().await.unwrap();
+        socket.write_all(rest.as_bytes()).await.unwrap();
+        socket.shutdown().await.unwrap();
+        String::from_utf8_lossy(&request).to_string()
+    });
+
+    (base_url, handle)
+}
+
+/// Whether the head and the body announced by its Content-Length have arrived
+fn request_complete(request: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(request);
+    let Some(end) = text.find("\r\n\r\n") else {
+        return false;
+    };
+    let length = text[..end]
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())
+                .flatten()
+        })
+        .unwrap_or(0);
+    request.len() >= end + 4 + length
+}
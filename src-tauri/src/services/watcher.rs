@@ -0,0 +1,627 @@
+// Watcher Service
+// Watches configured project roots and emits projects:changed when a KSPD
+// project appears, disappears, or has its manifest/metadata modified. Also
+// watches the config file and emits app:settings-changed when its settings
+// change, whether saved from the Settings window or edited externally.
+
+use crate::events::{
+    app_events, ProjectChangeKind, ProjectsChangedEvent, SettingChange, SettingsChangeSource,
+    SettingsChangedEvent,
+};
+use crate::services::{config, discovery, project_state, settings};
+use crate::types::{Project, ProjectRoot, SettingsConfig};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{mpsc, Mutex};
+
+/// Quiet period used to coalesce bursts of filesystem events (e.g. a parse run)
+const DEBOUNCE: tokio::time::Duration = tokio::time::Duration::from_millis(400);
+
+struct ProjectWatcherHandle {
+    roots: Vec<ProjectRoot>,
+    _watcher: RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+}
+
+static PROJECT_WATCHER: OnceLock<Arc<Mutex<Option<ProjectWatcherHandle>>>> = OnceLock::new();
+
+fn project_watcher_state() -> Arc<Mutex<Option<ProjectWatcherHandle>>> {
+    PROJECT_WATCHER
+        .get_or_init(|| Arc::new(Mutex::new(None)))
+        .clone()
+}
+
+/// Start watching the configured project roots, or restart the watcher if the
+/// roots changed since it was started. Safe to call repeatedly.
+pub async fn ensure_project_watcher(app: &AppHandle) {
+    let roots = match discovery::get_project_roots() {
+        Ok(roots) => roots,
+        Err(e) => {
+            tracing::warn!("Project watcher not started: {}", e);
+            return;
+        }
+    };
+
+    let state = project_watcher_state();
+    let mut guard = state.lock().await;
+
+    if let Some(existing) = guard.as_ref() {
+        if existing.roots == roots && !existing.task.is_finished() {
+            return;
+        }
+    }
+
+    if let Some(existing) = guard.take() {
+        existing.task.abort();
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let _ = tx.send(res);
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Failed to create project watcher: {}", e);
+            return;
+        }
+    };
+
+    for root in &roots {
+        let path = Path::new(&root.path);
+        if !path.is_dir() {
+            tracing::warn!("Not watching missing project root: {}", root.path);
+            continue;
+        }
+        if let Err(e) = watcher.watch(path, RecursiveMode::Recursive) {
+            tracing::warn!("Failed to watch project root {}: {}", root.path, e);
+        }
+    }
+
+    let discovered = snapshot(&roots);
+    tracing::info!(
+        "Watching {} project roots ({} projects)",
+        roots.len(),
+        discovered.len()
+    );
+
+    let task = tokio::spawn(run_project_watcher(
+        app.clone(),
+        roots.clone(),
+        discovered,
+        rx,
+    ));
+
+    *guard = Some(ProjectWatcherHandle {
+        roots,
+        _watcher: watcher,
+        task,
+    });
+}
+
+/// `discovered` holds the projects as scanned; change events are computed on
+/// the listed view of them (organization state applied, archived dropped), so
+/// they match what `discover_projects` returns.
+async fn run_project_watcher(
+    app: AppHandle,
+    roots: Vec<ProjectRoot>,
+    mut discovered: HashMap<String, Project>,
+    mut rx: mpsc::UnboundedReceiver<notify::Result<Event>>,
+) {
+    let mut known = listed(&discovered);
+
+    while let Some(first) = rx.recv().await {
+        let mut paths = Vec::new();
+        collect_paths(first, &mut paths);
+
+        // Drain until the filesystem has been quiet for DEBOUNCE
+        let mut closed = false;
+        loop {
+            match tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                Ok(Some(res)) => collect_paths(res, &mut paths),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        if !paths.is_empty() {
+            // Re-read only the projects the events point at; anything else
+            // (e.g. a folder of projects moved in) needs a full scan
+            discovered = match affected_projects(&roots, &discovered, &paths) {
+                Some(affected) => refresh(&roots, discovered, affected),
+                None => snapshot(&roots),
+            };
+            let current = listed(&discovered);
+            let touched = touched_projects(&known, &current, &paths);
+
+            for change in diff_projects(&known, &current, &touched) {
+                tracing::debug!("Project {:?}: {}", change.change, change.path);
+                let _ = app.emit(app_events::PROJECTS_CHANGED, change);
+            }
+
+            known = current;
+        }
+
+        if closed {
+            return;
+        }
+    }
+}
+
+fn collect_paths(res: notify::Result<Event>, paths: &mut Vec<PathBuf>) {
+    match res {
+        Ok(event) => {
+            if !matches!(event.kind, EventKind::Access(_)) {
+                paths.extend(event.paths);
+            }
+        }
+        Err(e) => tracing::debug!("Project watcher error: {}", e),
+    }
+}
+
+/// Current projects across all roots, keyed by path
+fn snapshot(roots: &[ProjectRoot]) -> HashMap<String, Project> {
+    discovery::discover_in_roots(roots)
+        .projects
+        .into_iter()
+        .map(|p| (p.path.clone(), p))
+        .collect()
+}
+
+/// Project directories the changed paths may affect, or None when a change
+/// can't be attributed to single projects. A path counts for the known project
+/// containing it, known projects below it (a moved or removed folder), and the
+/// nearest project directory above it under a root (a new project).
+fn affected_projects(
+    roots: &[ProjectRoot],
+    discovered: &HashMap<String, Project>,
+    paths: &[PathBuf],
+) -> Option<HashSet<PathBuf>> {
+    let mut affected = HashSet::new();
+
+    for path in paths {
+        if let Some(known) = discovered
+            .keys()
+            .map(Path::new)
+            .find(|p| path.starts_with(p))
+        {
+            affected.insert(known.to_path_buf());
+            continue;
+        }
+        affected.extend(
+            discovered
+                .keys()
+                .map(Path::new)
+                .filter(|p| p.starts_with(path))
+                .map(Path::to_path_buf),
+        );
+
+        let in_root = |dir: &Path| {
+            roots
+                .iter()
+                .any(|root| dir.starts_with(&root.path) && dir != Path::new(&root.path))
+        };
+        if let Some(project) = path
+            .ancestors()
+            .take_while(|dir| in_root(dir))
+            .find(|dir| discovery::is_kspd(dir))
+        {
+            affected.insert(project.to_path_buf());
+        } else if path.is_dir() {
+            return None;
+        }
+    }
+
+    Some(affected)
+}
+
+/// `discovered` with the `affected` project directories re-read
+fn refresh(
+    roots: &[ProjectRoot],
+    mut discovered: HashMap<String, Project>,
+    affected: HashSet<PathBuf>,
+) -> HashMap<String, Project> {
+    for path in affected {
+        let key = path.to_string_lossy().to_string();
+        match discovery::discover_project_at(roots, &path) {
+            Some(project) => discovered.insert(key, project),
+            None => discovered.remove(&key),
+        };
+    }
+    discovered
+}
+
+/// Discovered projects as listed, keyed by path
+fn listed(discovered: &HashMap<String, Project>) -> HashMap<String, Project> {
+    let projects = discovered.values().cloned().collect();
+    match project_state::apply_user_state(projects, false) {
+        Ok(projects) => projects.into_iter().map(|p| (p.path.clone(), p)).collect(),
+        Err(e) => {
+            tracing::warn!("Failed to apply project state: {}", e);
+            discovered.clone()
+        }
+    }
+}
+
+/// Paths of projects whose manifest.json or metadata/ changed
+fn touched_projects(
+    known: &HashMap<String, Project>,
+    current: &HashMap<String, Project>,
+    paths: &[PathBuf],
+) -> HashSet<String> {
+    let mut touched = HashSet::new();
+
+    for project_path in known.keys().chain(current.keys()) {
+        let root = Path::new(project_path);
+        let hit = paths.iter().any(|p| {
+            p.strip_prefix(root)
+                .ok()
+                .and_then(|rel| rel.components().next())
+                .map(|c| c.as_os_str() == "manifest.json" || c.as_os_str() == "metadata")
+                .unwrap_or(false)
+        });
+        if hit {
+            touched.insert(project_path.clone());
+        }
+    }
+
+    touched
+}
+
+/// Compute change events between two snapshots, ordered by path.
+/// A project counts as modified when its listed fields changed or its
+/// manifest/metadata was touched.
+fn diff_projects(
+    known: &HashMap<String, Project>,
+    current: &HashMap<String, Project>,
+    touched: &HashSet<String>,
+) -> Vec<ProjectsChangedEvent> {
+    let mut changes = Vec::new();
+
+    for (path, project) in current {
+        let change = match known.get(path) {
+            None => Some(ProjectChangeKind::Added),
+            Some(previous) if previous != project || touched.contains(path) => {
+                Some(ProjectChangeKind::Modified)
+            }
+            Some(_) => None,
+        };
+        if let Some(change) = change {
+            changes.push(ProjectsChangedEvent {
+                change,
+                project_id: project.id.clone(),
+                path: path.clone(),
+                project: Some(project.clone()),
+            });
+        }
+    }
+
+    for (path, project) in known {
+        if !current.contains_key(path) {
+            changes.push(ProjectsChangedEvent {
+                change: ProjectChangeKind::Removed,
+                project_id: project.id.clone(),
+                path: path.clone(),
+                project: None,
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+// ---------------------------------------------------------------------------
+// Config watcher — settings changes from the app or external edits
+// ---------------------------------------------------------------------------
+
+struct ConfigWatcherHandle {
+    _watcher: RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+}
+
+static CONFIG_WATCHER: OnceLock<Arc<Mutex<Option<ConfigWatcherHandle>>>> = OnceLock::new();
+
+/// Settings as of the last change event, the baseline for the next diff
+static LAST_SETTINGS: OnceLock<Arc<Mutex<Option<SettingsConfig>>>> = OnceLock::new();
+
+fn config_watcher_state() -> Arc<Mutex<Option<ConfigWatcherHandle>>> {
+    CONFIG_WATCHER
+        .get_or_init(|| Arc::new(Mutex::new(None)))
+        .clone()
+}
+
+fn last_settings_state() -> Arc<Mutex<Option<SettingsConfig>>> {
+    LAST_SETTINGS
+        .get_or_init(|| Arc::new(Mutex::new(None)))
+        .clone()
+}
+
+/// Start watching the config file for external edits. Safe to call repeatedly.
+pub async fn ensure_config_watcher(app: &AppHandle) {
+    let state = config_watcher_state();
+    let mut guard = state.lock().await;
+
+    if guard
+        .as_ref()
+        .is_some_and(|existing| !existing.task.is_finished())
+    {
+        return;
+    }
+
+    let (dir, path) = match (config::config_dir(), config::config_path()) {
+        (Ok(dir), Ok(path)) => (dir, path),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::warn!("Config watcher not started: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        tracing::warn!("Config watcher not started: {}", e);
+        return;
+    }
+
+    {
+        let last = last_settings_state();
+        let mut last = last.lock().await;
+        if last.is_none() {
+            *last = config::load_settings().ok();
+        }
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let _ = tx.send(res);
+    }) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Failed to create config watcher: {}", e);
+            return;
+        }
+    };
+
+    // Saves replace the file by rename, so watch the directory rather than
+    // the file itself
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        tracing::warn!("Failed to watch config directory {:?}: {}", dir, e);
+        return;
+    }
+
+    tracing::info!("Watching config file {:?}", path);
+    let task = tokio::spawn(run_config_watcher(app.clone(), path, rx));
+
+    *guard = Some(ConfigWatcherHandle {
+        _watcher: watcher,
+        task,
+    });
+}
+
+async fn run_config_watcher(
+    app: AppHandle,
+    config_path: PathBuf,
+    mut rx: mpsc::UnboundedReceiver<notify::Result<Event>>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut paths = Vec::new();
+        collect_paths(first, &mut paths);
+
+        let mut closed = false;
+        loop {
+            match tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                Ok(Some(res)) => collect_paths(res, &mut paths),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        if paths
+            .iter()
+            .any(|p| p.file_name() == config_path.file_name())
+        {
+            match config::load_settings() {
+                Ok(current) => {
+                    publish_settings(&app, None, current, SettingsChangeSource::External).await
+                }
+                // Likely a half-finished edit; the next save triggers another event
+                Err(e) => tracing::warn!("Ignoring config change: {}", e),
+            }
+        }
+
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Record `current` as the settings in effect and emit app:settings-changed
+/// with the fields that changed since the last recorded settings (`previous`
+/// when nothing was recorded yet). Emits nothing when no field changed, so a
+/// save from the app is not reported again by the config watcher.
+pub async fn publish_settings(
+    app: &AppHandle,
+    previous: Option<SettingsConfig>,
+    current: SettingsConfig,
+    source: SettingsChangeSource,
+) {
+    let changes = {
+        let last = last_settings_state();
+        let mut last = last.lock().await;
+        record_settings(&mut last, previous, &current)
+    };
+    if changes.is_empty() {
+        return;
+    }
+
+    tracing::info!(
+        "Settings changed ({:?}): {}",
+        source,
+        changes
+            .iter()
+            .map(|c| c.field.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    // Project roots may have changed
+    ensure_project_watcher(app).await;
+
+    // A running daemon keeps the provider environment it was started with
+    let daemon_restart_required = settings::requires_daemon_restart(&changes);
+    if daemon_restart_required {
+        tracing::warn!("Provider settings changed; a running daemon must be restarted to use them");
+    }
+
+    let _ = app.emit(
+        app_events::APP_SETTINGS_CHANGED,
+        SettingsChangedEvent {
+            source,
+            changes,
+            settings: current,
+            daemon_restart_required,
+        },
+    );
+}
+
+fn record_settings(
+    last: &mut Option<SettingsConfig>,
+    previous: Option<SettingsConfig>,
+    current: &SettingsConfig,
+) -> Vec<SettingChange> {
+    let changes = match last.take().or(previous) {
+        Some(baseline) => settings::diff_settings(&baseline, current),
+        None => Vec::new(),
+    };
+    *last = Some(current.clone());
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(path: &str, scene_count: usize) -> Project {
+        Project {
+            id: Project::id_from_path(path),
+            title: "Test".to_string(),
+            author: None,
+            path: path.to_string(),
+            scene_count,
+            ..Default::default()
+        }
+    }
+
+    fn snapshot_of(projects: &[Project]) -> HashMap<String, Project> {
+        projects
+            .iter()
+            .map(|p| (p.path.clone(), p.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_projects_added_removed_modified() {
+        let known = snapshot_of(&[project("/r/a.kspd", 1), project("/r/b.kspd", 1)]);
+        let current = snapshot_of(&[project("/r/a.kspd", 2), project("/r/c.kspd", 0)]);
+
+        let changes = diff_projects(&known, &current, &HashSet::new());
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].change, ProjectChangeKind::Modified);
+        assert_eq!(changes[0].project.as_ref().unwrap().scene_count, 2);
+        assert_eq!(changes[1].change, ProjectChangeKind::Removed);
+        assert!(changes[1].project.is_none());
+        assert_eq!(changes[2].change, ProjectChangeKind::Added);
+    }
+
+    #[test]
+    fn test_record_settings_reports_each_change_once() {
+        let saved = SettingsConfig {
+            provider: "groq".to_string(),
+            ..Default::default()
+        };
+
+        // First save: diffed against the settings on disk before it
+        let mut last = None;
+        let changes = record_settings(&mut last, Some(SettingsConfig::default()), &saved);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "provider");
+
+        // The watcher then sees the same settings in the file
+        assert!(record_settings(&mut last, None, &saved).is_empty());
+
+        // An external edit is diffed against the recorded settings
+        let edited = SettingsConfig {
+            model: Some("llama-3.1-8b-instant".to_string()),
+            ..saved.clone()
+        };
+        let changes = record_settings(&mut last, Some(SettingsConfig::default()), &edited);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "model");
+    }
+
+    #[test]
+    fn test_diff_projects_unchanged_is_silent() {
+        let known = snapshot_of(&[project("/r/a.kspd", 1)]);
+        let changes = diff_projects(&known, &known.clone(), &HashSet::new());
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_affected_projects_without_rescan() {
+        let dir = std::env::temp_dir().join(format!("khaos_watch_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("a.kspd/metadata")).unwrap();
+        std::fs::create_dir_all(dir.join("new.kspd/metadata")).unwrap();
+        std::fs::create_dir_all(dir.join("folder")).unwrap();
+
+        let roots = vec![ProjectRoot::new(dir.to_str().unwrap())];
+        let a = dir.join("a.kspd");
+        let discovered = snapshot_of(&[project(a.to_str().unwrap(), 1)]);
+
+        // Writes inside a known project and a new one
+        let paths = vec![
+            a.join("metadata/scenes.json"),
+            dir.join("new.kspd/metadata/scenes.json"),
+            dir.join("notes.txt"),
+        ];
+        let affected = affected_projects(&roots, &discovered, &paths).unwrap();
+        assert_eq!(affected, HashSet::from([a.clone(), dir.join("new.kspd")]));
+
+        // Removing a folder affects the known projects below it
+        let paths = vec![dir.join("gone")];
+        let gone = snapshot_of(&[project(dir.join("gone/b.kspd").to_str().unwrap(), 1)]);
+        let affected = affected_projects(&roots, &gone, &paths).unwrap();
+        assert_eq!(affected, HashSet::from([dir.join("gone/b.kspd")]));
+
+        // A plain folder may hold projects that were moved in
+        let paths = vec![dir.join("folder")];
+        assert!(affected_projects(&roots, &discovered, &paths).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_touched_projects_manifest_and_metadata_only() {
+        let known = snapshot_of(&[project("/r/a.kspd", 1), project("/r/b.kspd", 1)]);
+        let paths = vec![
+            PathBuf::from("/r/a.kspd/metadata/analysis/scn_001.json"),
+            PathBuf::from("/r/b.kspd/notes.txt"),
+        ];
+
+        let touched = touched_projects(&known, &known, &paths);
+
+        assert!(touched.contains("/r/a.kspd"));
+        assert!(!touched.contains("/r/b.kspd"));
+
+        let changes = diff_projects(&known, &known, &touched);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].change, ProjectChangeKind::Modified);
+    }
+}
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(crate = "serde")]
 pub struct Project {
     pub id: String,
@@ -11,16 +11,160 @@ pub struct Project {
     pub path: String,
     pub scene_count: usize,
     pub modified: i64,  // Unix timestamp
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub character_count: usize,
+    #[serde(default)]
+    pub location_count: usize,
+    /// True once metadata/artifacts/story-storage.db exists
+    #[serde(default)]
+    pub ingested: bool,
+    #[serde(default)]
+    pub analysis_coverage: AnalysisCoverage,
+    /// Total size of the project directory in bytes
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// User-assigned tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Position in the pinned list, if pinned
+    #[serde(default)]
+    pub pinned: Option<usize>,
+    /// Archived projects are hidden from default discovery
+    #[serde(default)]
+    pub archived: bool,
+    /// When the project was last opened (Unix timestamp), if ever
+    #[serde(default)]
+    pub last_opened: Option<i64>,
+    /// Dashboard section shown when the project was last open
+    #[serde(default)]
+    pub last_section: Option<String>,
 }
 
-/// Manifest.json structure found in KSPD projects
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One entry in the recent projects history (persisted in the UI config)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct RecentProject {
+    pub id: String,
+    /// Project path when it was opened, used to prune projects that no longer exist
+    #[serde(default)]
+    pub path: String,
+    /// Unix timestamp of the last open
+    #[serde(default)]
+    pub opened_at: i64,
+    /// Dashboard section last shown for the project
+    #[serde(default)]
+    pub section: Option<String>,
+}
+
+/// Share of entities with saved analysis, as percentages (0-100)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct AnalysisCoverage {
+    pub scenes: f64,
+    pub characters: f64,
+    pub locations: f64,
+}
+
+/// User organization state for one project, persisted in the UI config
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct ProjectUserState {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Manifest.json structure found in KSPD projects.
+/// Fields not modelled here are kept in `extra` so edits round-trip them, as
+/// are modelled fields whose value has an unexpected type (e.g. a string
+/// `draft_number`), so one odd field never hides the rest of the manifest.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
 #[serde(crate = "serde")]
 pub struct ProjectManifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logline: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact: Option<ManifestContact>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ProjectManifest {
+    /// Keys managed by the typed fields above
+    pub const FIELDS: &'static [&'static str] = &[
+        "title",
+        "author",
+        "description",
+        "logline",
+        "genre",
+        "draft_number",
+        "contact",
+    ];
+}
+
+impl<'de> Deserialize<'de> for ProjectManifest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut extra = serde_json::Map::<String, serde_json::Value>::deserialize(deserializer)?;
+
+        fn take<T: serde::de::DeserializeOwned>(
+            extra: &mut serde_json::Map<String, serde_json::Value>,
+            key: &str,
+        ) -> Option<T> {
+            let value = extra.remove(key)?;
+            if value.is_null() {
+                return None;
+            }
+            match serde_json::from_value(value.clone()) {
+                Ok(typed) => Some(typed),
+                Err(_) => {
+                    extra.insert(key.to_string(), value);
+                    None
+                }
+            }
+        }
+
+        Ok(Self {
+            title: take(&mut extra, "title"),
+            author: take(&mut extra, "author"),
+            description: take(&mut extra, "description"),
+            logline: take(&mut extra, "logline"),
+            genre: take(&mut extra, "genre"),
+            draft_number: take(&mut extra, "draft_number"),
+            contact: take(&mut extra, "contact"),
+            extra,
+        })
+    }
+}
+
+/// Contact details printed on a title page
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct ManifestContact {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
 }
 
 impl Project {
@@ -35,6 +179,22 @@ impl Project {
     }
 }
 
+/// Error encountered while scanning a single project root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct RootError {
+    pub root: String,
+    pub error: String,
+}
+
+/// Projects discovered across all configured roots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct DiscoveryReport {
+    pub projects: Vec<Project>,
+    pub errors: Vec<RootError>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct SystemInfo {
@@ -51,6 +211,42 @@ pub struct CollisionInfo {
     pub suggested_names: Vec<String>,
 }
 
+/// A template a new project can be scaffolded from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// False for templates supplied by the user in the config directory
+    pub builtin: bool,
+}
+
+/// Contents listing stored as bundle.json inside a project bundle archive
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub title: String,
+    /// Directory name of the exported project
+    pub source_name: String,
+    /// Unix timestamp of the export
+    pub created_at: i64,
+    pub includes_analysis: bool,
+    pub includes_storage: bool,
+    pub files: Vec<BundleFile>,
+}
+
+/// One project file in a bundle, with its size and SHA-256 checksum
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct BundleFile {
+    /// Path relative to the project directory, "/"-separated
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct ImportResult {
@@ -74,16 +270,98 @@ pub struct ProviderValidationResult {
     pub valid: bool,
     pub checks_run: Vec<String>,
     pub errors: Vec<String>,
+    /// Outcome of a live request, set only by the connection test
+    #[serde(default)]
+    pub connection: Option<ConnectionTest>,
 }
 
-// Settings types
+/// A model offered by a provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct ProviderModel {
+    pub id: String,
+    pub display_name: Option<String>,
+    /// Maximum context length in tokens, when known
+    pub context_window: Option<u32>,
+    /// e.g. "chat", "tools", "vision", "reasoning"
+    pub capabilities: Vec<String>,
+}
+
+/// Where a model list came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde", rename_all = "snake_case")]
+pub enum ModelSource {
+    Api,
+    Ollama,
+    Catalog,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "serde")]
+pub struct ProviderModelList {
+    pub provider: String,
+    pub models: Vec<ProviderModel>,
+    pub source: ModelSource,
+    pub fetched_at: i64,
+    /// Why the provider could not be asked, when falling back to the catalog
+    pub error: Option<String>,
+}
+
+/// How a live request to a provider turned out
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde", rename_all = "snake_case")]
+pub enum ConnectionOutcome {
+    Ok,
+    AuthFailed,
+    ModelNotFound,
+    RateLimited,
+    Unreachable,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct ConnectionTest {
+    pub outcome: ConnectionOutcome,
+    /// Round trip of the test request (None when no request was made)
+    pub latency_ms: Option<u64>,
+    pub status_code: Option<u16>,
+    pub base_url: Option<String>,
+    pub message: Option<String>,
+}
+
+// Settings types
+
+/// Settings fields of the config file; missing fields take their defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde", default)]
 pub struct SettingsConfig {
     pub provider: String,
     pub model: Option<String>,
     pub projects_root: Option<String>,
+    #[serde(default)]
+    pub project_roots: Vec<ProjectRoot>,
+    /// Number of entries kept in the recent projects history
+    #[serde(default = "default_recent_projects_limit")]
+    pub recent_projects_limit: usize,
+    /// Sampling temperature for analysis runs (provider default when None)
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Maximum tokens generated per analysis request (provider default when None)
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// User-defined providers, listed after the built-ins
+    #[serde(default)]
+    pub custom_providers: Vec<CustomProvider>,
+    /// Ollama server URL (OLLAMA_HOST or localhost:11434 when None)
+    #[serde(default)]
+    pub ollama_host: Option<String>,
+    /// khaos-tools binary, overriding the khaos-tui config and PATH
+    #[serde(default)]
+    pub khaos_tools_path: Option<String>,
+    /// khaos-wfl binary, overriding the khaos-tui config and PATH
+    #[serde(default)]
+    pub khaos_wfl_path: Option<String>,
 }
 
 impl Default for SettingsConfig {
@@ -92,11 +370,89 @@ impl Default for SettingsConfig {
             provider: "ollama".to_string(),
             model: None,
             projects_root: None,
+            project_roots: Vec::new(),
+            recent_projects_limit: DEFAULT_RECENT_PROJECTS_LIMIT,
+            temperature: None,
+            max_tokens: None,
+            custom_providers: Vec::new(),
+            ollama_host: None,
+            khaos_tools_path: None,
+            khaos_wfl_path: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SettingsConfig {
+    /// Built-in providers followed by custom ones. A custom provider whose ID
+    /// is already taken is left out.
+    pub fn providers(&self) -> Vec<ProviderInfo> {
+        let mut providers = ProviderInfo::all();
+        for custom in &self.custom_providers {
+            if !providers.iter().any(|p| p.id == custom.id) {
+                providers.push(custom.to_provider_info());
+            }
+        }
+        providers
+    }
+
+    pub fn find_provider(&self, id: &str) -> Option<ProviderInfo> {
+        self.providers().into_iter().find(|p| p.id == id)
+    }
+
+    /// The configured model, or the provider's default when none is set
+    pub fn effective_model(&self) -> Option<String> {
+        match self.model.as_deref().map(str::trim) {
+            Some(model) if !model.is_empty() => Some(model.to_string()),
+            _ => self.find_provider(&self.provider).map(|p| p.default_model),
+        }
+    }
+}
+
+/// Default length of the recent projects history
+pub const DEFAULT_RECENT_PROJECTS_LIMIT: usize = 10;
+
+/// Longest recent projects history that can be configured
+pub const MAX_RECENT_PROJECTS_LIMIT: usize = 50;
+
+fn default_recent_projects_limit() -> usize {
+    DEFAULT_RECENT_PROJECTS_LIMIT
+}
+
+/// Default number of directory levels scanned below a project root
+pub const DEFAULT_ROOT_MAX_DEPTH: usize = 3;
+
+fn default_root_max_depth() -> usize {
+    DEFAULT_ROOT_MAX_DEPTH
+}
+
+/// A directory scanned for KSPD projects
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+pub struct ProjectRoot {
+    pub path: String,
+    /// Directory levels below the root to scan (1 = direct children only)
+    #[serde(default = "default_root_max_depth")]
+    pub max_depth: usize,
+    /// Glob patterns a project must match to be listed (empty = all)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Glob patterns for directories to skip entirely
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl ProjectRoot {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            max_depth: DEFAULT_ROOT_MAX_DEPTH,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct ProviderInfo {
     pub id: String,
@@ -105,6 +461,18 @@ pub struct ProviderInfo {
     pub default_model: String,
     pub requires_key: bool,
     pub env_var: Option<String>,
+    /// API base URL passed to the analysis tools (custom providers)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Dispatched to the tools as an OpenAI-compatible endpoint
+    #[serde(default)]
+    pub openai_compatible: bool,
+    /// Secret store entry holding the key, when not the provider's own entry
+    #[serde(default)]
+    pub secret_ref: Option<String>,
+    /// Defined in settings rather than built in
+    #[serde(default)]
+    pub custom: bool,
 }
 
 impl ProviderInfo {
@@ -117,6 +485,7 @@ impl ProviderInfo {
                 default_model: "qwen3".to_string(),
                 requires_key: false,
                 env_var: None,
+                ..Default::default()
             },
             ProviderInfo {
                 id: "openai".to_string(),
@@ -125,6 +494,7 @@ impl ProviderInfo {
                 default_model: "gpt-4o".to_string(),
                 requires_key: true,
                 env_var: Some("OPENAI_API_KEY".to_string()),
+                ..Default::default()
             },
             ProviderInfo {
                 id: "mistralai".to_string(),
@@ -133,6 +503,7 @@ impl ProviderInfo {
                 default_model: "mistral-large-latest".to_string(),
                 requires_key: true,
                 env_var: Some("MISTRALAI_API_KEY".to_string()),
+                ..Default::default()
             },
             ProviderInfo {
                 id: "anthropic".to_string(),
@@ -141,6 +512,7 @@ impl ProviderInfo {
                 default_model: "claude-sonnet-4-20250514".to_string(),
                 requires_key: true,
                 env_var: Some("ANTHROPIC_API_KEY".to_string()),
+                ..Default::default()
             },
             ProviderInfo {
                 id: "groq".to_string(),
@@ -149,6 +521,7 @@ impl ProviderInfo {
                 default_model: "llama-3.3-70b-versatile".to_string(),
                 requires_key: true,
                 env_var: Some("GROQ_API_KEY".to_string()),
+                ..Default::default()
             },
             ProviderInfo {
                 id: "mock".to_string(),
@@ -157,6 +530,7 @@ impl ProviderInfo {
                 default_model: "mock-model".to_string(),
                 requires_key: false,
                 env_var: None,
+                ..Default::default()
             },
         ]
     }
@@ -166,11 +540,110 @@ impl ProviderInfo {
     }
 }
 
+/// A user-defined provider, e.g. a local vLLM or LM Studio server or an
+/// internal gateway
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct CustomProvider {
+    pub id: String,
+    pub title: String,
+    pub base_url: String,
+    /// Env var the analysis tools read the API key from
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Secret store entry holding the API key
+    #[serde(default)]
+    pub secret_ref: Option<String>,
+    pub default_model: String,
+    #[serde(default)]
+    pub openai_compatible: bool,
+}
+
+impl CustomProvider {
+    /// Provider entry for the merged list. A key is required when an env var
+    /// or secret is named; with only a secret, the key is passed to the tools
+    /// as OPENAI_API_KEY (OpenAI-compatible) or <ID>_API_KEY.
+    pub fn to_provider_info(&self) -> ProviderInfo {
+        let env_var = self.api_key_env.clone().or_else(|| {
+            self.secret_ref.as_ref().map(|_| {
+                if self.openai_compatible {
+                    "OPENAI_API_KEY".to_string()
+                } else {
+                    let id: String = self
+                        .id
+                        .chars()
+                        .map(|c| {
+                            if c.is_ascii_alphanumeric() {
+                                c.to_ascii_uppercase()
+                            } else {
+                                '_'
+                            }
+                        })
+                        .collect();
+                    format!("{}_API_KEY", id)
+                }
+            })
+        });
+
+        ProviderInfo {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            description: format!("Custom provider at {}", self.base_url),
+            default_model: self.default_model.clone(),
+            requires_key: env_var.is_some(),
+            env_var,
+            base_url: Some(self.base_url.clone()),
+            openai_compatible: self.openai_compatible,
+            secret_ref: self.secret_ref.clone(),
+            custom: true,
+        }
+    }
+}
+
+/// State of the secret store used for provider API keys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct SecretStoreStatus {
+    /// "secret-service" or "encrypted-file"
+    pub backend: String,
+    /// The encrypted file needs a passphrase before keys can be read or stored
+    pub locked: bool,
+    /// Whether the encrypted file has been created yet
+    pub initialized: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct ProviderKeyStatus {
+    pub provider: String,
+    /// A non-empty key is saved in the secret store
+    pub stored: bool,
+    /// The provider's env var is set in the app's environment
+    pub env_set: bool,
+    pub error: Option<String>,
+}
+
+/// A model installed on the Ollama server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "serde")]
+pub struct OllamaModel {
+    pub name: String,
+    /// Size on disk in bytes
+    pub size: u64,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    pub modified_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct OllamaStatus {
-    pub installed: bool,
+    pub host: String,
+    pub reachable: bool,
+    pub version: Option<String>,
     pub model_available: bool,
+    pub models: Vec<OllamaModel>,
     pub error: Option<String>,
 }
 
@@ -231,7 +704,7 @@ pub struct GraphSummary {
     pub total_relationships: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct SceneAnalysis {
     pub title: Option<String>,
@@ -263,7 +736,7 @@ pub struct SceneAnalysis {
     pub location_significance: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct CharacterAnalysis {
     pub summary: Option<String>,
@@ -293,7 +766,7 @@ pub struct CharacterAnalysis {
     pub genre_fit: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct LocationAnalysis {
     pub summary: Option<String>,
@@ -344,6 +817,20 @@ pub struct AnalysisResult {
     pub error: Option<String>,
 }
 
+/// Provider, model and generation parameters used for a saved analysis
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct AnalysisRun {
+    pub provider: String,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub max_tokens: Option<u32>,
+    /// "cli" or "daemon"
+    pub via: String,
+    /// Unix timestamp (seconds) when the run completed
+    pub recorded_at: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "serde")]
 pub struct AnalysisIndex {
@@ -382,3 +869,57 @@ pub struct DaemonStatus {
     pub busy: bool,
     pub queue_depth: usize,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "serde")]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Moved,
+    Modified,
+}
+
+/// An analysis (or summary) field whose value differs between two projects.
+/// Lists are joined with "; ".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct SceneChange {
+    pub kind: ChangeKind,
+    pub slugline: String,
+    /// Scene index in the base project (None when added)
+    pub base_index: Option<usize>,
+    /// Scene index in the other project (None when removed)
+    pub other_index: Option<usize>,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct EntityChange {
+    pub kind: ChangeKind,
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two KSPD projects (e.g. two drafts). Only entities that
+/// were added, removed, moved or modified are listed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "serde")]
+pub struct ProjectComparison {
+    pub base_title: String,
+    pub base_path: String,
+    pub other_title: String,
+    pub other_path: String,
+    pub scenes: Vec<SceneChange>,
+    pub characters: Vec<EntityChange>,
+    pub locations: Vec<EntityChange>,
+}
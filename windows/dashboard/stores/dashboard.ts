@@ -1,6 +1,7 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { save } from '@tauri-apps/plugin-dialog'
 import type {
   ProjectSummary,
   SceneSummary,
@@ -14,6 +15,12 @@ import type {
   AnalysisResult,
   DashboardSection,
   DaemonStatus,
+  SettingsConfig,
+  SettingsChangedEvent,
+  SettingsLoadResult,
+  ProviderInfo,
+  Project,
+  ExportOption,
 } from '@khaos/shared'
 
 export const useDashboardStore = defineStore('dashboard', () => {
@@ -34,6 +41,10 @@ export const useDashboardStore = defineStore('dashboard', () => {
   const error = ref<string | null>(null)
   const analyzing = ref<boolean>(false)
   const daemonStatus = ref<DaemonStatus | null>(null)
+  const settings = ref<SettingsConfig | null>(null)
+  const providers = ref<ProviderInfo[]>([])
+  const exporting = ref<boolean>(false)
+  const lastExportPath = ref<string | null>(null)
 
   function syncAnalyzingFlag() {
     analyzing.value = Object.values(analysisStates.value).some((s) => s === 'analyzing')
@@ -72,7 +83,37 @@ export const useDashboardStore = defineStore('dashboard', () => {
     return { analyzed, total: items.length }
   })
 
+  // Provider and model the next analysis will use
+  const activeModel = computed(() => {
+    if (!settings.value) return null
+    const provider = providers.value.find((p) => p.id === settings.value?.provider)
+    return {
+      provider: provider?.title ?? settings.value.provider,
+      model: settings.value.model || provider?.default_model || '',
+    }
+  })
+
   // Actions
+  // Also called on app:settings-changed so the header follows Settings edits
+  async function loadSettings() {
+    try {
+      const result = await invoke<SettingsLoadResult>('load_settings')
+      settings.value = result.config
+      providers.value = result.providers
+    } catch (err) {
+      console.error('[Dashboard Store] load_settings FAILED:', err)
+    }
+  }
+
+  // app:settings-changed: follow the new settings and flag provider changes
+  // that a running daemon won't see until it is restarted
+  async function onSettingsChanged(event: SettingsChangedEvent) {
+    await loadSettings()
+    if (event.daemon_restart_required && daemonStatus.value?.running) {
+      error.value = 'Provider settings changed. Restart the analysis daemon to use them for queued analysis.'
+    }
+  }
+
   async function loadProject(path: string, title?: string) {
     console.log('[Dashboard Store] loadProject called with path:', path)
     projectPath.value = path
@@ -87,6 +128,7 @@ export const useDashboardStore = defineStore('dashboard', () => {
     detailOpen.value = false
     detailAnalysis.value = null
     daemonStatus.value = null
+    lastExportPath.value = null
     loading.value = true
     error.value = null
 
@@ -133,6 +175,17 @@ export const useDashboardStore = defineStore('dashboard', () => {
 
       console.log('[Dashboard Store] Project loaded:', { scenes: scenes.value.length, characters: characters.value.length, locations: locations.value.length })
 
+      // Return to the section the project was last left on
+      try {
+        const recent = await invoke<Project[]>('get_recent_projects')
+        const lastSection = recent.find((p) => p.path === path)?.last_section
+        if (lastSection) {
+          currentSection.value = lastSection
+        }
+      } catch (err) {
+        console.warn('[Dashboard Store] Recent section lookup failed:', err)
+      }
+
       // Check daemon status
       try {
         daemonStatus.value = await invoke<DaemonStatus>('get_daemon_status', { projectPath: path })
@@ -155,6 +208,12 @@ export const useDashboardStore = defineStore('dashboard', () => {
     selectedId.value = null
     detailOpen.value = false
     detailAnalysis.value = null
+
+    if (projectPath.value) {
+      invoke('set_recent_project_section', { projectPath: projectPath.value, section }).catch((err) => {
+        console.warn('[Dashboard Store] Failed to save section:', err)
+      })
+    }
   }
 
   async function selectItem(id: string) {
@@ -281,6 +340,37 @@ export const useDashboardStore = defineStore('dashboard', () => {
     }
   }
 
+  /**
+   * Ask for a destination with the save dialog and export the project there.
+   * Returns the written path, or null when the dialog was cancelled or the export failed.
+   */
+  async function exportProject(option: ExportOption): Promise<string | null> {
+    const outputPath = await save({
+      defaultPath: `${projectTitle.value}.${option.extension}`,
+      filters: [{ name: option.label, extensions: [option.extension] }],
+    })
+    if (!outputPath) return null
+
+    exporting.value = true
+    try {
+      let written: string
+      if (option.kind === 'breakdown') {
+        written = await invoke<string>('export_breakdown', { projectPath: projectPath.value, format: option.format, outputPath })
+      } else if (option.kind === 'fountain') {
+        written = await invoke<string>('export_fountain', { projectPath: projectPath.value, outputPath })
+      } else {
+        written = await invoke<string>('export_analysis', { projectPath: projectPath.value, scope: 'all', format: option.format, outputPath })
+      }
+      lastExportPath.value = written
+      return written
+    } catch (e) {
+      error.value = `Export failed: ${String(e)}`
+      return null
+    } finally {
+      exporting.value = false
+    }
+  }
+
   // Handle daemon events — called from app.vue event listeners
   function onAnalysisStarted(payload: { item_type: string; item_id: string; operation: string }) {
     analyzing.value = true
@@ -340,12 +430,18 @@ export const useDashboardStore = defineStore('dashboard', () => {
     error,
     analyzing,
     daemonStatus,
+    settings,
+    exporting,
+    lastExportPath,
     // Getters
     currentItems,
     currentItemsWithState,
     selectedItem,
     analysisProgress,
+    activeModel,
     // Actions
+    loadSettings,
+    onSettingsChanged,
     loadProject,
     switchSection,
     selectItem,
@@ -353,6 +449,7 @@ export const useDashboardStore = defineStore('dashboard', () => {
     analyzeItem,
     analyzeAll,
     refreshAnalysisStates,
+    exportProject,
     onAnalysisStarted,
     onAnalysisProgress,
     onAnalysisCompleted,
@@ -326,6 +326,37 @@ describe('Projects Store', () => {
     })
   })
 
+  // ===== ACTIONS: applyProjectChange =====
+  describe('applyProjectChange action', () => {
+    it('should add, update and remove projects by path', () => {
+      const store = useProjectsStore()
+      const older = createMockProject({ id: 'a', path: '/r/a.kspd', modified: 100 })
+      store.projects = [older]
+
+      const added = createMockProject({ id: 'b', path: '/r/b.kspd', modified: 200 })
+      store.applyProjectChange({ change: 'added', project_id: 'b', path: '/r/b.kspd', project: added })
+      expect(store.projects.map((p) => p.id)).toEqual(['b', 'a'])
+
+      const renamed = { ...older, title: 'Renamed' }
+      store.applyProjectChange({ change: 'modified', project_id: 'a', path: '/r/a.kspd', project: renamed })
+      expect(store.projects).toHaveLength(2)
+      expect(store.projects.find((p) => p.id === 'a')?.title).toBe('Renamed')
+
+      store.applyProjectChange({ change: 'removed', project_id: 'b', path: '/r/b.kspd' })
+      expect(store.projects.map((p) => p.id)).toEqual(['a'])
+    })
+
+    it('should keep pinned projects first', () => {
+      const store = useProjectsStore()
+      store.projects = [createMockProject({ id: 'pinned', path: '/r/p.kspd', modified: 1, pinned: 0 })]
+
+      const fresh = createMockProject({ id: 'fresh', path: '/r/f.kspd', modified: 999 })
+      store.applyProjectChange({ change: 'added', project_id: 'fresh', path: '/r/f.kspd', project: fresh })
+
+      expect(store.projects.map((p) => p.id)).toEqual(['pinned', 'fresh'])
+    })
+  })
+
   // ===== ERROR HANDLING =====
   describe('Error Handling', () => {
     it('should store error message when invoke fails', async () => {
@@ -1,12 +1,12 @@
 import { defineStore } from 'pinia'
 import { ref, computed, watch } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
-import { getCurrentWindow } from '@tauri-apps/api/window'
-import type { Project } from '@khaos/shared'
+import type { Project, ProjectsChangedEvent } from '@khaos/shared'
 
 export const useProjectsStore = defineStore('projects', () => {
   // State
   const projects = ref<Project[]>([])
+  const recentHistory = ref<Project[]>([])
   const search = ref<string>('')
   const selected = ref<Project | null>(null)
   const loading = ref<boolean>(false)
@@ -29,6 +29,36 @@ export const useProjectsStore = defineStore('projects', () => {
     }
   }
 
+  /**
+   * Load recently opened projects (most recent first) for the "last opened" section.
+   * The backend resolves history entries to full projects and prunes missing ones.
+   */
+  const loadRecentProjects = async (): Promise<void> => {
+    try {
+      recentHistory.value = await invoke<Project[]>('get_recent_projects')
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err)
+      console.error('Failed to load recent projects:', errorMessage)
+    }
+  }
+
+  /**
+   * Apply a projects:changed event from the root watcher without rescanning.
+   * Keeps the backend order: pinned projects first, then most recently modified.
+   */
+  const applyProjectChange = (event: ProjectsChangedEvent): void => {
+    const others = projects.value.filter((p) => p.path !== event.path)
+    if (event.change === 'removed' || !event.project) {
+      projects.value = others
+      return
+    }
+
+    projects.value = [...others, event.project].sort((a, b) => {
+      const pinnedOrder = (a.pinned ?? Number.MAX_SAFE_INTEGER) - (b.pinned ?? Number.MAX_SAFE_INTEGER)
+      return pinnedOrder !== 0 ? pinnedOrder : b.modified - a.modified
+    })
+  }
+
   const setSearch = (query: string): void => {
     search.value = query
   }
@@ -55,10 +85,9 @@ export const useProjectsStore = defineStore('projects', () => {
   }
 
   /**
-   * Set project as active and handle recent projects + cross-window communication
-   * This method:
-   * 1. Calls backend to update recent projects list
-   * 2. Emits app event to notify other windows (Dashboard)
+   * Set project as active and refresh the recent projects history.
+   * The Dashboard is notified by open_dashboard_window, which sends the
+   * project path it needs.
    */
   const setActiveProject = async (project: Project): Promise<void> => {
     try {
@@ -66,15 +95,9 @@ export const useProjectsStore = defineStore('projects', () => {
       await invoke<void>('set_active_project', {
         projectId: project.id,
       })
+      await loadRecentProjects()
 
-      // Get current window and emit app event for Dashboard window to listen
-      const appWindow = getCurrentWindow()
-      await appWindow.emit('app:project-selected', {
-        projectId: project.id,
-        projectTitle: project.title,
-      })
-
-      console.log(`Project selected and broadcast: ${project.title}`)
+      console.log(`Project set as active: ${project.title}`)
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : String(err)
       error.value = `Failed to set active project: ${errorMessage}`
@@ -123,12 +146,15 @@ export const useProjectsStore = defineStore('projects', () => {
   return {
     // State
     projects,
+    recentHistory,
     search,
     selected,
     loading,
     error,
     // Actions
     loadProjects,
+    loadRecentProjects,
+    applyProjectChange,
     setSearch,
     selectProject,
     setActiveProject,
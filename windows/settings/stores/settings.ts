@@ -1,12 +1,21 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { getCurrentWindow } from '@tauri-apps/api/window'
 import type {
   SettingsConfig,
   ProviderInfo,
   SettingsLoadResult,
   ProviderValidationResult,
   DaemonCheckResult,
+  SecretStoreStatus,
+  ProviderKeyStatus,
+  ConnectionTest,
+  CustomProvider,
+  OllamaStatus,
+  OllamaPullProgress,
+  ProviderModelList,
+  SettingsChangedEvent,
 } from '@khaos/shared'
 
 export const useSettingsStore = defineStore('settings', () => {
@@ -20,6 +29,18 @@ export const useSettingsStore = defineStore('settings', () => {
   const validation = ref<ProviderValidationResult | null>(null)
   const daemonStatus = ref<DaemonCheckResult | null>(null)
   const dirty = ref(false)
+  const secretStore = ref<SecretStoreStatus | null>(null)
+  const keyStatus = ref<ProviderKeyStatus | null>(null)
+  // Result of the last live check of the provider's key
+  const keyTest = ref<ConnectionTest | null>(null)
+  const keyBusy = ref(false)
+  const ollamaStatus = ref<OllamaStatus | null>(null)
+  const ollamaChecking = ref(false)
+  const ollamaPull = ref<OllamaPullProgress | null>(null)
+  const providerModels = ref<ProviderModelList | null>(null)
+  const modelsLoading = ref(false)
+  // Config file edited elsewhere while this window had unsaved changes
+  const externalChange = ref(false)
 
   // Actions
   const loadSettings = async (): Promise<void> => {
@@ -31,6 +52,8 @@ export const useSettingsStore = defineStore('settings', () => {
       config.value = result.config
       providers.value = result.providers
       dirty.value = false
+      externalChange.value = false
+      loadProviderModels()
     } catch (err) {
       error.value = err instanceof Error ? err.message : String(err)
       console.error('Failed to load settings:', error.value)
@@ -46,6 +69,7 @@ export const useSettingsStore = defineStore('settings', () => {
     try {
       await invoke<void>('save_settings', { config: config.value })
       dirty.value = false
+      externalChange.value = false
     } catch (err) {
       error.value = err instanceof Error ? err.message : String(err)
       console.error('Failed to save settings:', error.value)
@@ -54,10 +78,23 @@ export const useSettingsStore = defineStore('settings', () => {
     }
   }
 
+  // Reload on external edits unless that would discard unsaved changes
+  const onSettingsChanged = async (event: SettingsChangedEvent): Promise<void> => {
+    if (event.source !== 'external') return
+    if (dirty.value) {
+      externalChange.value = true
+    } else {
+      await loadSettings()
+    }
+  }
+
   const selectProvider = (providerId: string): void => {
     config.value = { ...config.value, provider: providerId, model: undefined }
     dirty.value = true
     validation.value = null
+    keyStatus.value = null
+    keyTest.value = null
+    loadProviderModels()
   }
 
   const setModel = (model: string): void => {
@@ -68,6 +105,147 @@ export const useSettingsStore = defineStore('settings', () => {
     dirty.value = true
   }
 
+  const setGenerationParams = (params: {
+    temperature?: number
+    max_tokens?: number
+  }): void => {
+    config.value = { ...config.value, ...params }
+    dirty.value = true
+  }
+
+  // Custom providers are merged into the list locally; the backend
+  // validates them on save
+  const addCustomProvider = (provider: CustomProvider): void => {
+    if (providers.value.some((p) => p.id === provider.id)) {
+      error.value = `Provider ID '${provider.id}' is already in use`
+      return
+    }
+    config.value = {
+      ...config.value,
+      custom_providers: [...(config.value.custom_providers ?? []), provider],
+    }
+    providers.value = [
+      ...providers.value,
+      {
+        id: provider.id,
+        title: provider.title,
+        description: `Custom provider at ${provider.base_url}`,
+        default_model: provider.default_model,
+        requires_key: !!(provider.api_key_env || provider.secret_ref),
+        env_var: provider.api_key_env,
+        base_url: provider.base_url,
+        openai_compatible: provider.openai_compatible,
+        secret_ref: provider.secret_ref,
+        custom: true,
+      },
+    ]
+    dirty.value = true
+  }
+
+  const removeCustomProvider = (providerId: string): void => {
+    config.value = {
+      ...config.value,
+      custom_providers: (config.value.custom_providers ?? []).filter(
+        (p) => p.id !== providerId,
+      ),
+    }
+    providers.value = providers.value.filter((p) => !(p.custom && p.id === providerId))
+    if (config.value.provider === providerId) {
+      selectProvider('ollama')
+    }
+    dirty.value = true
+  }
+
+  // Saved settings decide the provider's endpoint and key, so unsaved custom
+  // providers fall back to an empty list
+  const loadProviderModels = async (refresh = false): Promise<void> => {
+    const providerId = config.value.provider
+    modelsLoading.value = true
+    try {
+      const result = await invoke<ProviderModelList>('list_provider_models', {
+        providerId,
+        refresh,
+      })
+      if (config.value.provider === providerId) {
+        providerModels.value = result
+      }
+    } catch {
+      providerModels.value = null
+    } finally {
+      modelsLoading.value = false
+    }
+  }
+
+  const setOllamaHost = (host: string): void => {
+    config.value = { ...config.value, ollama_host: host.trim() || undefined }
+    dirty.value = true
+    ollamaStatus.value = null
+  }
+
+  const checkOllama = async (): Promise<void> => {
+    ollamaChecking.value = true
+    try {
+      ollamaStatus.value = await invoke<OllamaStatus>('get_ollama_status', {
+        host: config.value.ollama_host ?? null,
+        model: effectiveModel.value || null,
+      })
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    } finally {
+      ollamaChecking.value = false
+    }
+  }
+
+  const pullOllamaModel = async (model: string): Promise<void> => {
+    ollamaPull.value = { model, status: 'starting', done: false }
+    const unlisten = await getCurrentWindow().listen<OllamaPullProgress>(
+      'ollama:pull-progress',
+      (event) => {
+        if (event.payload.model === model) {
+          ollamaPull.value = event.payload
+        }
+      },
+    )
+
+    try {
+      await invoke<void>('pull_ollama_model', {
+        model,
+        host: config.value.ollama_host ?? null,
+      })
+      await checkOllama()
+    } catch (err) {
+      ollamaPull.value = {
+        model,
+        status: 'error',
+        done: true,
+        error: err instanceof Error ? err.message : String(err),
+      }
+    } finally {
+      unlisten()
+    }
+  }
+
+  // Live request to the provider; only run when the user asks for it
+  const testConnection = async (baseUrl?: string): Promise<void> => {
+    validating.value = true
+    validation.value = null
+
+    try {
+      validation.value = await invoke<ProviderValidationResult>(
+        'test_provider_connection',
+        { config: config.value, baseUrl: baseUrl ?? null },
+      )
+    } catch (err) {
+      validation.value = {
+        valid: false,
+        checks_run: [],
+        errors: [err instanceof Error ? err.message : String(err)],
+      }
+    } finally {
+      validating.value = false
+    }
+  }
+
   const validateProvider = async (): Promise<void> => {
     validating.value = true
     validation.value = null
@@ -102,6 +280,84 @@ export const useSettingsStore = defineStore('settings', () => {
     }
   }
 
+  const loadSecretStoreStatus = async (): Promise<void> => {
+    try {
+      secretStore.value = await invoke<SecretStoreStatus>('get_secret_store_status')
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    }
+  }
+
+  const unlockSecretStore = async (passphrase: string): Promise<void> => {
+    keyBusy.value = true
+    try {
+      secretStore.value = await invoke<SecretStoreStatus>('unlock_secret_store', {
+        passphrase,
+      })
+      await loadProviderKeyStatus()
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    } finally {
+      keyBusy.value = false
+    }
+  }
+
+  const setProviderKey = async (apiKey: string): Promise<void> => {
+    keyBusy.value = true
+    try {
+      await invoke<void>('set_provider_key', {
+        providerId: config.value.provider,
+        apiKey,
+      })
+      keyTest.value = null
+      await loadSecretStoreStatus()
+      await loadProviderKeyStatus()
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    } finally {
+      keyBusy.value = false
+    }
+  }
+
+  const loadProviderKeyStatus = async (): Promise<void> => {
+    try {
+      keyStatus.value = await invoke<ProviderKeyStatus>('get_provider_key_status', {
+        providerId: config.value.provider,
+      })
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    }
+  }
+
+  const testProviderKey = async (): Promise<void> => {
+    keyBusy.value = true
+    keyTest.value = null
+    try {
+      keyTest.value = await invoke<ConnectionTest>('test_provider_key', {
+        providerId: config.value.provider,
+      })
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    } finally {
+      keyBusy.value = false
+    }
+  }
+
+  const clearProviderKey = async (): Promise<void> => {
+    keyBusy.value = true
+    try {
+      await invoke<void>('clear_provider_key', {
+        providerId: config.value.provider,
+      })
+      keyTest.value = null
+      await loadProviderKeyStatus()
+    } catch (err) {
+      error.value = err instanceof Error ? err.message : String(err)
+    } finally {
+      keyBusy.value = false
+    }
+  }
+
   // Getters
   const currentProvider = computed((): ProviderInfo | undefined => {
     return providers.value.find((p) => p.id === config.value.provider)
@@ -126,13 +382,38 @@ export const useSettingsStore = defineStore('settings', () => {
     validation,
     daemonStatus,
     dirty,
+    secretStore,
+    keyStatus,
+    keyTest,
+    keyBusy,
+    ollamaStatus,
+    ollamaChecking,
+    ollamaPull,
+    providerModels,
+    modelsLoading,
+    externalChange,
     // Actions
     loadSettings,
     saveSettings,
+    onSettingsChanged,
     selectProvider,
     setModel,
+    setGenerationParams,
+    addCustomProvider,
+    removeCustomProvider,
+    loadProviderModels,
+    setOllamaHost,
+    checkOllama,
+    pullOllamaModel,
     validateProvider,
+    testConnection,
     checkDaemonConnection,
+    loadSecretStoreStatus,
+    unlockSecretStore,
+    setProviderKey,
+    loadProviderKeyStatus,
+    testProviderKey,
+    clearProviderKey,
     // Getters
     currentProvider,
     effectiveModel,